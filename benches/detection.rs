@@ -0,0 +1,41 @@
+use camera_simple_detect::camera::{
+    detect_cameras_from, has_virtual_camera_from, CameraDevice, DetectionResult,
+};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+const VIDEO_CAMERA_INTERFACE: &str = "{e5323777-f976-4f5b-9b55-b94699c46e44}";
+
+// A virtual camera listed first, followed by unremarkable USB cameras.
+fn devices() -> Vec<CameraDevice> {
+    let mut devices = vec![CameraDevice::from_name_and_path(
+        "OBS Virtual Camera",
+        &format!("\\\\?\\root#image#0000#{VIDEO_CAMERA_INTERFACE}"),
+    )];
+    devices.extend((0..31).map(|index| {
+        CameraDevice::from_name_and_path(
+            &format!("USB Camera {index}"),
+            &format!("\\\\?\\usb#vid_1e4e&pid_{index:04x}&mi_00#7&{index:x}&0&0000#{VIDEO_CAMERA_INTERFACE}"),
+        )
+    }));
+    devices
+}
+
+fn first_device_virtual(c: &mut Criterion) {
+    let devices = devices();
+    let mut group = c.benchmark_group("first device virtual");
+    group.bench_function("has_virtual_camera_from", |b| {
+        b.iter(|| has_virtual_camera_from(black_box(&devices)))
+    });
+    group.bench_function("classify every device", |b| {
+        b.iter(|| {
+            matches!(
+                detect_cameras_from(black_box(&devices)),
+                DetectionResult::VirtualCamera | DetectionResult::Mixed { .. }
+            )
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, first_device_virtual);
+criterion_main!(benches);
@@ -0,0 +1,21 @@
+fn main() {
+    #[cfg(feature = "capi")]
+    generate_header();
+}
+
+#[cfg(feature = "capi")]
+fn generate_header() {
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+
+    println!("cargo:rerun-if-changed=src/ffi.rs");
+
+    cbindgen::Builder::new()
+        .with_crate(crate_dir)
+        .with_language(cbindgen::Language::C)
+        .with_include_guard("CAMERA_SIMPLE_DETECT_H")
+        .with_parse_expand_features(&["capi"])
+        .generate()
+        .expect("failed to generate C bindings")
+        .write_to_file(std::path::Path::new(&out_dir).join("camera_simple_detect.h"));
+}
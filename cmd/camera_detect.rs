@@ -0,0 +1,186 @@
+//! Prints what the crate sees on this machine, for field diagnostics.
+//!
+//! `list` prints one tab-separated line per device under a header, `detect`
+//! prints the overall result and `json` prints the devices and the result as
+//! JSON. Missing values are printed as `-`.
+//!
+//! Exit codes: 0 when a real camera is present, 1 when only virtual cameras
+//! are, 2 when there is no camera, 3 on a usage or enumeration error and 4
+//! when only network cameras are present.
+
+use std::process::ExitCode;
+
+use camera_simple_detect::camera::{CameraDevice, DetectionOptions, DetectionResult, Detector};
+
+const USAGE: &str =
+    "usage: camera-detect <list|detect|json> [--backend mf|dshow|all] [--rules <file>]";
+
+const EXIT_ERROR: u8 = 3;
+const EXIT_NETWORK_ONLY: u8 = 4;
+
+enum Command {
+    List,
+    Detect,
+    Json,
+}
+
+struct Args {
+    command: Command,
+    options: DetectionOptions,
+    rules: Option<String>,
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("camera-detect: {message}\n{USAGE}");
+            return ExitCode::from(EXIT_ERROR);
+        }
+    };
+    match run(args) {
+        Ok(result) => ExitCode::from(exit_code(result)),
+        Err(message) => {
+            eprintln!("camera-detect: {message}");
+            ExitCode::from(EXIT_ERROR)
+        }
+    }
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let command = match args.next().as_deref() {
+        Some("list") => Command::List,
+        Some("detect") => Command::Detect,
+        Some("json") => Command::Json,
+        Some(other) => return Err(format!("unknown command `{other}`")),
+        None => return Err("missing command".to_string()),
+    };
+
+    let mut parsed = Args {
+        command,
+        options: DetectionOptions::default(),
+        rules: None,
+    };
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("`{arg}` needs a value"));
+        match arg.as_str() {
+            "--backend" => parsed.options = backend_options(&value()?)?,
+            "--rules" => parsed.rules = Some(value()?),
+            other => return Err(format!("unknown option `{other}`")),
+        }
+    }
+    Ok(parsed)
+}
+
+// Only the Windows backends can be chosen; elsewhere the platform's single
+// backend always runs.
+fn backend_options(backend: &str) -> Result<DetectionOptions, String> {
+    let only = |media_foundation, directshow| DetectionOptions {
+        media_foundation,
+        directshow,
+        registry: false,
+        setupapi: false,
+    };
+    match backend {
+        "mf" => Ok(only(true, false)),
+        "dshow" => Ok(only(false, true)),
+        "all" => Ok(DetectionOptions::default()),
+        other => Err(format!("unknown backend `{other}`")),
+    }
+}
+
+fn run(args: Args) -> Result<DetectionResult, String> {
+    let detector = match &args.rules {
+        Some(path) => Detector::from_rules_file(path).map_err(|error| error.to_string())?,
+        None => Detector::default(),
+    }
+    .with_enumerator(args.options.enumerator());
+
+    let devices = detector.enumerate().map_err(|error| error.to_string())?;
+    let result = detector.detect_from(&devices);
+    match args.command {
+        Command::List => print_table(&detector, &devices),
+        Command::Detect => println!("{}", result_label(result)),
+        Command::Json => print_json(&detector, &devices, result),
+    }
+    Ok(result)
+}
+
+fn print_table(detector: &Detector, devices: &[CameraDevice]) {
+    println!("name\tvid:pid\tpath\tsource\tclassification");
+    for device in devices {
+        let vid_pid = match (&device.vid, &device.pid) {
+            (None, None) => "-".to_string(),
+            (vid, pid) => format!("{}:{}", or_dash(vid), or_dash(pid)),
+        };
+        println!(
+            "{}\t{vid_pid}\t{}\t{}\t{}",
+            device.name,
+            or_dash(&device.device_path),
+            source_label(device),
+            kind_label(detector, device),
+        );
+    }
+}
+
+fn print_json(detector: &Detector, devices: &[CameraDevice], result: DetectionResult) {
+    let devices = devices
+        .iter()
+        .map(|device| {
+            serde_json::json!({
+                "device": device,
+                "kind": kind_label(detector, device),
+                "classification": detector.classify(device),
+            })
+        })
+        .collect::<Vec<_>>();
+    let output = serde_json::json!({ "result": result, "devices": devices });
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&output).expect("camera devices serialize to JSON")
+    );
+}
+
+fn exit_code(result: DetectionResult) -> u8 {
+    match result {
+        DetectionResult::RealCamera | DetectionResult::Mixed { .. } => 0,
+        DetectionResult::VirtualCamera => 1,
+        DetectionResult::NetworkCamera => EXIT_NETWORK_ONLY,
+        _ => 2,
+    }
+}
+
+// The serde names, so the output matches the `json` command.
+fn result_label(result: DetectionResult) -> String {
+    match result {
+        DetectionResult::RealCamera => "real_camera".to_string(),
+        DetectionResult::VirtualCamera => "virtual_camera".to_string(),
+        DetectionResult::Mixed {
+            real_count,
+            virtual_count,
+        } => format!("mixed\t{real_count}\t{virtual_count}"),
+        DetectionResult::NetworkCamera => "network_camera".to_string(),
+        _ => "no_camera".to_string(),
+    }
+}
+
+fn source_label(device: &CameraDevice) -> String {
+    serde_json::to_value(device.enumeration_source)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+// Detecting on the device alone applies the detector's config, including
+// whether network cameras count as real.
+fn kind_label(detector: &Detector, device: &CameraDevice) -> &'static str {
+    match detector.detect_from(std::slice::from_ref(device)) {
+        DetectionResult::VirtualCamera => "virtual",
+        DetectionResult::NetworkCamera => "network",
+        _ => "real",
+    }
+}
+
+fn or_dash(value: &Option<String>) -> &str {
+    value.as_deref().unwrap_or("-")
+}
@@ -0,0 +1,169 @@
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+use super::detector::Detector;
+use super::device_enum::{summarize, CameraDevice, DetectionResult};
+use super::enumerator::CameraEnumerator;
+use super::error::EnumerationError;
+use super::watch::{CameraWatcher, WatchError};
+
+type Cache = Arc<Mutex<Option<(Instant, Vec<CameraDevice>)>>>;
+
+/// A `Detector` that reuses its device list for `ttl` instead of
+/// enumerating on every call.
+///
+/// Callers that find the cache stale while another one is enumerating wait
+/// for that enumeration instead of starting their own. Failed enumerations
+/// are not cached.
+pub struct CachedDetector {
+    detector: Detector,
+    ttl: Duration,
+    cache: Cache,
+    watcher: Option<CameraWatcher>,
+}
+
+impl CachedDetector {
+    pub fn new(detector: Detector, ttl: Duration) -> Self {
+        Self {
+            detector,
+            ttl,
+            cache: Cache::default(),
+            watcher: None,
+        }
+    }
+
+    pub fn with_enumerator(mut self, enumerator: impl CameraEnumerator + 'static) -> Self {
+        self.detector = self.detector.with_enumerator(Box::new(enumerator));
+        self.invalidate();
+        self
+    }
+
+    /// Also invalidates the cache whenever a camera is plugged in or
+    /// removed; see `CameraWatcher`.
+    pub fn invalidate_on_device_change(mut self) -> Result<Self, WatchError> {
+        let cache = Arc::clone(&self.cache);
+        self.watcher = Some(CameraWatcher::watch(move |_| {
+            *lock(&cache) = None;
+        })?);
+        Ok(self)
+    }
+
+    pub fn detector(&self) -> &Detector {
+        &self.detector
+    }
+
+    /// The cached devices, enumerating first when the cache is empty or
+    /// older than the TTL.
+    pub fn devices(&self) -> Result<Vec<CameraDevice>, EnumerationError> {
+        let mut cache = lock(&self.cache);
+        if let Some((cached_at, devices)) = cache.as_ref() {
+            if cached_at.elapsed() < self.ttl {
+                return Ok(devices.clone());
+            }
+        }
+        self.fill(&mut cache)
+    }
+
+    pub fn detect(&self) -> Result<DetectionResult, EnumerationError> {
+        Ok(summarize(self.devices()?, self.detector.config()).overall())
+    }
+
+    pub fn invalidate(&self) {
+        *lock(&self.cache) = None;
+    }
+
+    /// Enumerates now, whatever the age of the cache.
+    pub fn refresh(&self) -> Result<Vec<CameraDevice>, EnumerationError> {
+        self.fill(&mut lock(&self.cache))
+    }
+
+    // Runs with the lock held, which is what makes concurrent callers wait.
+    fn fill(
+        &self,
+        cache: &mut Option<(Instant, Vec<CameraDevice>)>,
+    ) -> Result<Vec<CameraDevice>, EnumerationError> {
+        *cache = None;
+        let devices = self.detector.enumerate()?;
+        *cache = Some((Instant::now(), devices.clone()));
+        Ok(devices)
+    }
+}
+
+// A panicking enumerator leaves the cache empty, which is still valid.
+fn lock(cache: &Cache) -> MutexGuard<'_, Option<(Instant, Vec<CameraDevice>)>> {
+    cache.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::camera::device_enum::EnumerationSource;
+
+    // Counts its calls and sleeps so concurrent callers overlap.
+    #[derive(Clone, Default)]
+    struct CountingEnumerator {
+        calls: Arc<AtomicUsize>,
+        fail: bool,
+    }
+
+    impl CameraEnumerator for CountingEnumerator {
+        fn enumerate(&self) -> Result<Vec<CameraDevice>, EnumerationError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            std::thread::sleep(Duration::from_millis(50));
+            if self.fail {
+                return Err(EnumerationError::DeviceEnumFailed {
+                    backend: EnumerationSource::MediaFoundation,
+                    hresult: -1,
+                });
+            }
+            Ok(vec![CameraDevice::from_name_and_path("Camera", "")])
+        }
+    }
+
+    #[test]
+    fn concurrent_callers_share_one_enumeration() {
+        let enumerator = CountingEnumerator::default();
+        let calls = Arc::clone(&enumerator.calls);
+        let cached = CachedDetector::new(Detector::default(), Duration::from_secs(60))
+            .with_enumerator(enumerator);
+
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| assert_eq!(cached.devices().unwrap().len(), 1));
+            }
+        });
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        cached.detect().unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        cached.invalidate();
+        cached.devices().unwrap();
+        cached.refresh().unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn expired_and_failed_results_are_not_reused() {
+        let enumerator = CountingEnumerator::default();
+        let calls = Arc::clone(&enumerator.calls);
+        let cached =
+            CachedDetector::new(Detector::default(), Duration::ZERO).with_enumerator(enumerator);
+        cached.devices().unwrap();
+        cached.devices().unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        let failing = CountingEnumerator {
+            fail: true,
+            ..CountingEnumerator::default()
+        };
+        let calls = Arc::clone(&failing.calls);
+        let cached = CachedDetector::new(Detector::default(), Duration::from_secs(60))
+            .with_enumerator(failing);
+        assert!(cached.devices().is_err());
+        assert!(cached.devices().is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,170 @@
+use std::time::{Duration, Instant};
+
+use super::device_enum::{classify_devices, enumerate_devices};
+use super::error::EnumerationError;
+
+const BUDGET_HEADROOM: u32 = 4;
+const MIN_STAGE_BUDGET: Duration = Duration::from_millis(50);
+const MAX_ENUMERATION_BUDGET: Duration = Duration::from_secs(5);
+const MAX_CLASSIFICATION_BUDGET: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PipelineStage {
+    Enumeration,
+    Classification,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CalibrationProfile {
+    pub stage_costs: Vec<(PipelineStage, Duration)>,
+    pub device_count: usize,
+    pub os_build: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StageBudgets {
+    pub enumeration: Duration,
+    pub classification: Duration,
+}
+
+impl Default for StageBudgets {
+    fn default() -> Self {
+        Self {
+            enumeration: MAX_ENUMERATION_BUDGET,
+            classification: MAX_CLASSIFICATION_BUDGET,
+        }
+    }
+}
+
+pub fn calibrate() -> CalibrationProfile {
+    let started = Instant::now();
+    let devices = enumerate_devices().unwrap_or_else(EnumerationError::into_partial_devices);
+    let enumeration = started.elapsed();
+
+    // classify_devices() enumerates again, so only the remainder is classification.
+    let started = Instant::now();
+    classify_devices();
+    let classification = started.elapsed().saturating_sub(enumeration);
+
+    CalibrationProfile {
+        stage_costs: vec![
+            (PipelineStage::Enumeration, enumeration),
+            (PipelineStage::Classification, classification),
+        ],
+        device_count: devices.len(),
+        os_build: current_os_build(),
+    }
+}
+
+pub fn derive_budgets(profile: &CalibrationProfile) -> StageBudgets {
+    let mut budgets = StageBudgets::default();
+
+    for (stage, cost) in &profile.stage_costs {
+        let (budget, cap) = match stage {
+            PipelineStage::Enumeration => (&mut budgets.enumeration, MAX_ENUMERATION_BUDGET),
+            PipelineStage::Classification => {
+                (&mut budgets.classification, MAX_CLASSIFICATION_BUDGET)
+            }
+        };
+        *budget = cost
+            .saturating_mul(BUDGET_HEADROOM)
+            .clamp(MIN_STAGE_BUDGET, cap);
+    }
+
+    budgets
+}
+
+pub fn needs_recalibration(
+    profile: &CalibrationProfile,
+    device_count: usize,
+    os_build: Option<&str>,
+) -> bool {
+    profile.device_count != device_count || profile.os_build.as_deref() != os_build
+}
+
+pub fn current_os_build() -> Option<String> {
+    #[cfg(windows)]
+    {
+        super::platform::windows::registry::read_registry_string(
+            windows::Win32::System::Registry::HKEY_LOCAL_MACHINE,
+            "SOFTWARE\\Microsoft\\Windows NT\\CurrentVersion",
+            "CurrentBuildNumber",
+        )
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        std::fs::read_to_string("/proc/sys/kernel/osrelease")
+            .ok()
+            .map(|release| release.trim().to_string())
+    }
+
+    #[cfg(not(any(windows, target_os = "linux")))]
+    {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(enumeration: Duration, classification: Duration) -> CalibrationProfile {
+        CalibrationProfile {
+            stage_costs: vec![
+                (PipelineStage::Enumeration, enumeration),
+                (PipelineStage::Classification, classification),
+            ],
+            device_count: 2,
+            os_build: Some("22631".to_string()),
+        }
+    }
+
+    #[test]
+    fn budgets_leave_headroom_over_the_measured_cost() {
+        let budgets = derive_budgets(&profile(
+            Duration::from_millis(300),
+            Duration::from_millis(20),
+        ));
+
+        assert_eq!(
+            budgets,
+            StageBudgets {
+                enumeration: Duration::from_millis(1200),
+                classification: Duration::from_millis(80),
+            }
+        );
+    }
+
+    #[test]
+    fn budgets_are_clamped() {
+        let fast = derive_budgets(&profile(Duration::ZERO, Duration::from_micros(10)));
+        assert_eq!(fast.enumeration, MIN_STAGE_BUDGET);
+        assert_eq!(fast.classification, MIN_STAGE_BUDGET);
+
+        let slow = derive_budgets(&profile(Duration::from_secs(60), Duration::MAX));
+        assert_eq!(slow, StageBudgets::default());
+    }
+
+    #[test]
+    fn unmeasured_stages_keep_the_default_budget() {
+        let profile = CalibrationProfile {
+            stage_costs: Vec::new(),
+            device_count: 0,
+            os_build: None,
+        };
+
+        assert_eq!(derive_budgets(&profile), StageBudgets::default());
+    }
+
+    #[test]
+    fn recalibrates_when_devices_or_os_build_change() {
+        let profile = profile(Duration::ZERO, Duration::ZERO);
+
+        assert!(!needs_recalibration(&profile, 2, Some("22631")));
+        assert!(needs_recalibration(&profile, 3, Some("22631")));
+        assert!(needs_recalibration(&profile, 2, Some("26100")));
+        assert!(needs_recalibration(&profile, 2, None));
+    }
+}
@@ -0,0 +1,268 @@
+use std::fmt;
+
+use super::device_enum::{enumerate_devices, CameraDevice};
+use super::error::EnumerationError;
+#[cfg(windows)]
+use super::platform::windows::ComApartment;
+
+/// A capture mode a camera natively offers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CameraMode {
+    pub width: u32,
+    pub height: u32,
+    pub fps_numerator: u32,
+    pub fps_denominator: u32,
+    /// The first field of the Media Foundation subtype GUID, which is the
+    /// FourCC (`YUY2`, `MJPG`, `NV12`, ...) for most video formats.
+    pub fourcc: u32,
+}
+
+/// A device together with the capture modes read from it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeviceFormats {
+    pub device: CameraDevice,
+    pub modes: Vec<CameraMode>,
+    /// The HRESULT the device failed to open with; `modes` is then empty.
+    pub error: Option<i32>,
+}
+
+/// What a camera can capture, as returned by `query_capabilities`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CameraCapabilities {
+    /// Distinct `(width, height)` pairs, in the order the device lists them.
+    pub supported_resolutions: Vec<(u32, u32)>,
+    /// Distinct frame rates in frames per second, in the same order.
+    pub supported_frame_rates: Vec<f64>,
+    /// Every native mode; unlike the lists above, it ties each resolution to
+    /// the rates and formats offered with it.
+    pub modes: Vec<CameraMode>,
+}
+
+impl CameraCapabilities {
+    fn from_modes(modes: Vec<CameraMode>) -> Self {
+        let mut supported_resolutions = Vec::new();
+        let mut supported_frame_rates = Vec::new();
+        for mode in &modes {
+            let resolution = (mode.width, mode.height);
+            if !supported_resolutions.contains(&resolution) {
+                supported_resolutions.push(resolution);
+            }
+            if mode.fps_denominator == 0 {
+                continue;
+            }
+            let frame_rate = f64::from(mode.fps_numerator) / f64::from(mode.fps_denominator);
+            if !supported_frame_rates.contains(&frame_rate) {
+                supported_frame_rates.push(frame_rate);
+            }
+        }
+        Self {
+            supported_resolutions,
+            supported_frame_rates,
+            modes,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CapabilityError {
+    /// The device has no symbolic link, or nothing is connected at it.
+    DeviceNotFound,
+    /// COM, Media Foundation or the device source could not be started.
+    ActivationFailed {
+        hresult: i32,
+    },
+    ReaderCreationFailed {
+        hresult: i32,
+    },
+    PlatformNotSupported,
+}
+
+impl CapabilityError {
+    pub fn hresult(&self) -> Option<i32> {
+        match self {
+            CapabilityError::ActivationFailed { hresult }
+            | CapabilityError::ReaderCreationFailed { hresult } => Some(*hresult),
+            CapabilityError::DeviceNotFound | CapabilityError::PlatformNotSupported => None,
+        }
+    }
+}
+
+impl fmt::Display for CapabilityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CapabilityError::DeviceNotFound => write!(f, "the device could not be found"),
+            CapabilityError::ActivationFailed { hresult } => {
+                write!(f, "could not activate the device (HRESULT {hresult:#010x})")
+            }
+            CapabilityError::ReaderCreationFailed { hresult } => write!(
+                f,
+                "could not create a source reader (HRESULT {hresult:#010x})"
+            ),
+            CapabilityError::PlatformNotSupported => {
+                write!(f, "capability queries are not supported on this platform")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CapabilityError {}
+
+/// The native media types of `device`, opened by its symbolic link and
+/// read through a Media Foundation source reader.
+///
+/// Some virtual cameras open but expose no media types; they get empty
+/// capabilities. Only implemented on Windows.
+pub fn query_capabilities(device: &CameraDevice) -> Result<CameraCapabilities, CapabilityError> {
+    read_capabilities(device).map(CameraCapabilities::from_modes)
+}
+
+/// Enumerates devices and reads the capture modes of each one.
+///
+/// Opening a device is slow and can briefly turn on its LED, which is why
+/// `enumerate_devices` does not do this. A device that fails to open is
+/// still listed, with its error.
+pub fn enumerate_devices_with_formats() -> Result<Vec<DeviceFormats>, EnumerationError> {
+    Ok(enumerate_devices()?
+        .into_iter()
+        .map(|device| match read_capabilities(&device) {
+            Ok(modes) => DeviceFormats {
+                device,
+                modes,
+                error: None,
+            },
+            Err(error) => DeviceFormats {
+                device,
+                modes: Vec::new(),
+                error: error.hresult(),
+            },
+        })
+        .collect())
+}
+
+fn read_capabilities(device: &CameraDevice) -> Result<Vec<CameraMode>, CapabilityError> {
+    #[cfg(windows)]
+    {
+        let symbolic_link = device
+            .device_path
+            .as_deref()
+            .ok_or(CapabilityError::DeviceNotFound)?;
+        read_native_media_types(symbolic_link)
+    }
+
+    #[cfg(not(windows))]
+    {
+        let _ = device;
+        Err(CapabilityError::PlatformNotSupported)
+    }
+}
+
+#[cfg(windows)]
+fn read_native_media_types(symbolic_link: &str) -> Result<Vec<CameraMode>, CapabilityError> {
+    use windows::Win32::Foundation::{ERROR_DEVICE_NOT_CONNECTED, ERROR_FILE_NOT_FOUND};
+    use windows::Win32::Media::MediaFoundation::{
+        MFCreateSourceReaderFromMediaSource, MFShutdown, MFStartup, MF_E_NO_MORE_TYPES,
+        MF_MT_FRAME_RATE, MF_MT_FRAME_SIZE, MF_MT_SUBTYPE, MF_SOURCE_READER_FIRST_VIDEO_STREAM,
+        MF_VERSION,
+    };
+
+    let activation_failed = |error: windows::core::Error| {
+        let hresult = error.code();
+        if hresult == ERROR_FILE_NOT_FOUND.to_hresult()
+            || hresult == ERROR_DEVICE_NOT_CONNECTED.to_hresult()
+        {
+            CapabilityError::DeviceNotFound
+        } else {
+            CapabilityError::ActivationFailed { hresult: hresult.0 }
+        }
+    };
+
+    let _com =
+        ComApartment::enter().map_err(|hresult| CapabilityError::ActivationFailed { hresult })?;
+
+    unsafe {
+        MFStartup(MF_VERSION, 0).map_err(activation_failed)?;
+
+        let result = (|| {
+            let source = create_device_source(symbolic_link).map_err(activation_failed)?;
+
+            let modes = (|| {
+                let reader =
+                    MFCreateSourceReaderFromMediaSource(&source, None).map_err(|error| {
+                        CapabilityError::ReaderCreationFailed {
+                            hresult: error.code().0,
+                        }
+                    })?;
+
+                let mut modes = Vec::new();
+                for index in 0.. {
+                    let media_type = match reader
+                        .GetNativeMediaType(MF_SOURCE_READER_FIRST_VIDEO_STREAM.0 as u32, index)
+                    {
+                        Ok(media_type) => media_type,
+                        Err(error) => {
+                            if error.code() != MF_E_NO_MORE_TYPES {
+                                log::debug!(
+                                    "stopped reading media types of {symbolic_link}: {error}"
+                                );
+                            }
+                            break;
+                        }
+                    };
+                    // Both attributes pack two u32 values into the high and low halves.
+                    let (Ok(frame_size), Ok(frame_rate), Ok(subtype)) = (
+                        media_type.GetUINT64(&MF_MT_FRAME_SIZE),
+                        media_type.GetUINT64(&MF_MT_FRAME_RATE),
+                        media_type.GetGUID(&MF_MT_SUBTYPE),
+                    ) else {
+                        continue;
+                    };
+                    modes.push(CameraMode {
+                        width: (frame_size >> 32) as u32,
+                        height: frame_size as u32,
+                        fps_numerator: (frame_rate >> 32) as u32,
+                        fps_denominator: frame_rate as u32,
+                        fourcc: subtype.data1,
+                    });
+                }
+                Ok(modes)
+            })();
+
+            source.Shutdown().ok();
+            modes
+        })();
+
+        MFShutdown().ok();
+
+        result
+    }
+}
+
+// Expects COM to be initialized and Media Foundation started.
+#[cfg(windows)]
+pub(crate) unsafe fn create_device_source(
+    symbolic_link: &str,
+) -> windows::core::Result<windows::Win32::Media::MediaFoundation::IMFMediaSource> {
+    use windows::core::HSTRING;
+    use windows::Win32::Media::MediaFoundation::{
+        MFCreateAttributes, MFCreateDeviceSource, MF_DEVSOURCE_ATTRIBUTE_SOURCE_TYPE,
+        MF_DEVSOURCE_ATTRIBUTE_SOURCE_TYPE_VIDCAP_GUID,
+        MF_DEVSOURCE_ATTRIBUTE_SOURCE_TYPE_VIDCAP_SYMBOLIC_LINK,
+    };
+
+    let mut attributes = None;
+    MFCreateAttributes(&mut attributes, 2)?;
+    let attributes = attributes.ok_or_else(windows::core::Error::empty)?;
+    attributes.SetGUID(
+        &MF_DEVSOURCE_ATTRIBUTE_SOURCE_TYPE,
+        &MF_DEVSOURCE_ATTRIBUTE_SOURCE_TYPE_VIDCAP_GUID,
+    )?;
+    attributes.SetString(
+        &MF_DEVSOURCE_ATTRIBUTE_SOURCE_TYPE_VIDCAP_SYMBOLIC_LINK,
+        &HSTRING::from(symbolic_link),
+    )?;
+    MFCreateDeviceSource(&attributes)
+}
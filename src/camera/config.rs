@@ -0,0 +1,335 @@
+use super::known_hardware::KNOWN_REAL_VIDS;
+use super::scoring::ScoreWeights;
+
+const DEFAULT_NAME_KEYWORDS: [&str; 15] = [
+    "virtual",
+    "obs",
+    "manycam",
+    "snap camera",
+    "xsplit",
+    "mmhmm",
+    "droidcam",
+    "iriun",
+    "contacam",
+    "streamlabs",
+    "camsip",
+    "v4l2 loopback",
+    "v4l2loopback",
+    "akvcam",
+    "reincubate", // Camo on macOS
+];
+
+// DirectShow source filter CLSIDs of known virtual cameras. Category and
+// interface class GUIDs such as CLSID_VideoInputDeviceCategory must not be
+// listed here: every capture device is registered under them.
+//
+// Sources: the virtual camera module of obs-studio's win-dshow plugin, and
+// CLSID_UnityCaptureService in schellingb/UnityCapture. e2eSoft VCam has no
+// published filter CLSID; its `e2esoftvcam` driver service catches it.
+const DEFAULT_CLSIDS: [&str; 2] = [
+    "{a3fce0f5-3493-419f-958a-aba1250ec20b}", // OBS Virtual Camera
+    "{5c2cd55c-92ad-4999-8666-912bd3e70010}", // Unity Video Capture
+];
+
+/// Known virtual cameras as `(name, vid, pid)`; the default VID/PID rules
+/// come from here.
+pub(crate) const KNOWN_VIRTUAL_CAMERAS: [(&str, &str, &str); 4] = [
+    ("OBS Virtual Camera", "0bda", "58f4"),
+    ("ManyCam Virtual Webcam", "0c45", "6366"),
+    ("Snap Camera", "2b7e", "f13a"),
+    ("DroidCam Source", "05a3", "9331"),
+];
+
+// Windows driver services installed by virtual camera software.
+const DEFAULT_DRIVER_SERVICES: [&str; 2] = ["obs-virtualcam", "e2esoftvcam"];
+
+// IP and RTSP cameras installed as DirectShow source filters.
+const DEFAULT_NETWORK_MARKERS: [&str; 7] = [
+    "rtsp://",
+    "rtsps://",
+    "http://",
+    "https://",
+    "onvif",
+    "ip camera",
+    "network camera",
+];
+
+const DEFAULT_VIRTUAL_THRESHOLD: f32 = 0.7;
+
+/// Rules used to tell virtual cameras from real ones.
+///
+/// The default holds the built-in lists; push to a list to extend it or
+/// assign it to replace the built-ins.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DetectionConfig {
+    /// Substrings matched against the name, manufacturer, driver and path.
+    pub name_keywords: Vec<String>,
+    pub clsids: Vec<String>,
+    /// VID/PID pairs; `*` matches any id.
+    pub vid_pids: Vec<(String, String)>,
+    pub whitelist: Whitelist,
+    /// Match name keywords and whitelisted names case-sensitively. IDs and
+    /// CLSIDs are always compared ignoring case.
+    pub case_sensitive: bool,
+    /// Also flag devices that report no manufacturer.
+    pub strict: bool,
+    /// Also flag Windows devices whose path does not come from a hardware
+    /// bus (USB, PCI or ACPI), such as root- or software-enumerated ones.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub strict_hardware_check: bool,
+    /// Driver service names, compared ignoring case against the whole
+    /// `driver` field.
+    #[cfg_attr(feature = "serde", serde(default = "default_driver_services"))]
+    pub driver_services: Vec<String>,
+    /// USB vendor IDs whose devices are real whatever their name, driver or
+    /// CLSID say; only a VID/PID rule or a missing USB path overrides them.
+    /// Checked after the whitelist and before every other rule. With a
+    /// `score_threshold`, they subtract points instead.
+    #[cfg_attr(feature = "serde", serde(default = "default_known_real_vids"))]
+    pub known_real_vids: Vec<String>,
+    /// Devices whose `score_device` is at or above this are virtual, unless
+    /// `score_threshold` is set.
+    #[cfg_attr(feature = "serde", serde(default = "default_virtual_threshold"))]
+    pub virtual_threshold: f32,
+    /// When set, devices are classified by the points of `classify_scored`
+    /// instead: those whose total is at or above this are virtual, and
+    /// `virtual_threshold` is not used.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub score_threshold: Option<i32>,
+    /// Substrings of the name, manufacturer or device path that mark a
+    /// network camera, compared ignoring case.
+    #[cfg_attr(feature = "serde", serde(default = "default_network_markers"))]
+    pub network_markers: Vec<String>,
+    /// Count network cameras as real instead of as `DeviceKind::Network`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub network_cameras_are_real: bool,
+    /// Points each signal adds in `classify_scored`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub score_weights: ScoreWeights,
+}
+
+impl Default for DetectionConfig {
+    fn default() -> Self {
+        Self {
+            name_keywords: DEFAULT_NAME_KEYWORDS.map(String::from).to_vec(),
+            clsids: DEFAULT_CLSIDS.map(String::from).to_vec(),
+            vid_pids: KNOWN_VIRTUAL_CAMERAS
+                .map(|(_, vid, pid)| (vid.to_string(), pid.to_string()))
+                .to_vec(),
+            whitelist: Whitelist::default(),
+            case_sensitive: false,
+            strict: false,
+            strict_hardware_check: false,
+            driver_services: default_driver_services(),
+            known_real_vids: default_known_real_vids(),
+            virtual_threshold: DEFAULT_VIRTUAL_THRESHOLD,
+            score_threshold: None,
+            network_markers: default_network_markers(),
+            network_cameras_are_real: false,
+            score_weights: ScoreWeights::default(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+fn default_virtual_threshold() -> f32 {
+    DEFAULT_VIRTUAL_THRESHOLD
+}
+
+fn default_driver_services() -> Vec<String> {
+    DEFAULT_DRIVER_SERVICES.map(String::from).to_vec()
+}
+
+fn default_network_markers() -> Vec<String> {
+    DEFAULT_NETWORK_MARKERS.map(String::from).to_vec()
+}
+
+fn default_known_real_vids() -> Vec<String> {
+    KNOWN_REAL_VIDS.iter().map(|vid| vid.to_string()).collect()
+}
+
+impl DetectionConfig {
+    pub fn add_name_keyword(mut self, keyword: &str) -> Self {
+        self.name_keywords.push(keyword.to_string());
+        self
+    }
+
+    pub fn remove_name_keyword(mut self, keyword: &str) -> Self {
+        self.name_keywords
+            .retain(|existing| !existing.eq_ignore_ascii_case(keyword));
+        self
+    }
+
+    pub fn add_vid_pid(mut self, vid: &str, pid: &str) -> Self {
+        self.vid_pids.push((vid.to_string(), pid.to_string()));
+        self
+    }
+
+    pub fn remove_vid_pid(mut self, vid: &str, pid: &str) -> Self {
+        self.vid_pids.retain(|(existing_vid, existing_pid)| {
+            !(existing_vid.eq_ignore_ascii_case(vid) && existing_pid.eq_ignore_ascii_case(pid))
+        });
+        self
+    }
+
+    pub fn add_clsid(mut self, clsid: &str) -> Self {
+        self.clsids.push(clsid.to_string());
+        self
+    }
+
+    pub fn add_known_real_vid(mut self, vid: &str) -> Self {
+        self.known_real_vids.push(vid.to_string());
+        self
+    }
+
+    pub fn add_network_marker(mut self, marker: &str) -> Self {
+        self.network_markers.push(marker.to_string());
+        self
+    }
+
+    pub fn with_network_cameras_as_real(mut self, network_cameras_are_real: bool) -> Self {
+        self.network_cameras_are_real = network_cameras_are_real;
+        self
+    }
+
+    pub fn add_allowlist_entry(mut self, name: &str) -> Self {
+        self.whitelist.names.push(name.to_string());
+        self
+    }
+
+    pub fn with_case_sensitive(mut self, case_sensitive: bool) -> Self {
+        self.case_sensitive = case_sensitive;
+        self
+    }
+
+    pub fn strict_mode(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    pub fn strict_hardware_check(mut self, strict_hardware_check: bool) -> Self {
+        self.strict_hardware_check = strict_hardware_check;
+        self
+    }
+
+    pub fn with_virtual_threshold(mut self, virtual_threshold: f32) -> Self {
+        self.virtual_threshold = virtual_threshold;
+        self
+    }
+
+    pub fn with_score_threshold(mut self, score_threshold: i32) -> Self {
+        self.score_threshold = Some(score_threshold);
+        self
+    }
+
+    pub fn with_score_weights(mut self, score_weights: ScoreWeights) -> Self {
+        self.score_weights = score_weights;
+        self
+    }
+
+    pub(crate) fn fold_case(&self, value: &str) -> String {
+        if self.case_sensitive {
+            value.to_string()
+        } else {
+            value.to_lowercase()
+        }
+    }
+}
+
+/// Devices that are always treated as real, whatever the other rules say.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Whitelist {
+    /// Substrings matched against the device name.
+    pub names: Vec<String>,
+    /// VID/PID pairs; `*` matches any id.
+    pub vid_pids: Vec<(String, String)>,
+}
+
+impl Whitelist {
+    pub fn add_name(mut self, name: &str) -> Self {
+        self.names.push(name.to_string());
+        self
+    }
+
+    pub fn add_vid_pid(mut self, vid: &str, pid: &str) -> Self {
+        self.vid_pids.push((vid.to_string(), pid.to_string()));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty() && self.vid_pids.is_empty()
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> Result<Self, ConfigParseError> {
+        serde_json::from_str(json).map_err(ConfigParseError::Json)
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn from_toml(toml: &str) -> Result<Self, ConfigParseError> {
+        toml::from_str(toml).map_err(ConfigParseError::Toml)
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ConfigParseError {
+    Json(serde_json::Error),
+    Toml(toml::de::Error),
+    Io(std::io::Error),
+    /// A value that parsed but is not a valid rule, such as a VID that is
+    /// not four hex digits.
+    Invalid {
+        field: String,
+        value: String,
+    },
+}
+
+#[cfg(feature = "serde")]
+impl std::fmt::Display for ConfigParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigParseError::Json(error) => write!(f, "invalid JSON config: {error}"),
+            ConfigParseError::Toml(error) => write!(f, "invalid TOML config: {error}"),
+            ConfigParseError::Io(error) => write!(f, "could not read config: {error}"),
+            ConfigParseError::Invalid { field, value } => {
+                write!(f, "invalid value {value:?} in `{field}`")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl std::error::Error for ConfigParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigParseError::Json(error) => Some(error),
+            ConfigParseError::Toml(error) => Some(error),
+            ConfigParseError::Io(error) => Some(error),
+            ConfigParseError::Invalid { .. } => None,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn whitelist_parses_from_json_and_toml() {
+        let expected = Whitelist::default()
+            .add_name("acme inspection")
+            .add_vid_pid("1234", "*");
+
+        let json = r#"{ "names": ["acme inspection"], "vid_pids": [["1234", "*"]] }"#;
+        let toml = "names = [\"acme inspection\"]\nvid_pids = [[\"1234\", \"*\"]]\n";
+
+        assert_eq!(Whitelist::from_json(json).unwrap(), expected);
+        assert_eq!(Whitelist::from_toml(toml).unwrap(), expected);
+        assert!(Whitelist::from_json("{}").unwrap().is_empty());
+    }
+}
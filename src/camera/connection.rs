@@ -0,0 +1,63 @@
+use super::device_enum::{CameraDevice, EnumerationSource};
+
+/// How a camera is attached to the machine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+#[non_exhaustive]
+pub enum CameraConnection {
+    /// Built into the machine, such as a laptop's lid camera.
+    Integrated,
+    /// A plugged-in USB camera.
+    Usb,
+    /// Streamed over the network, such as an IP camera.
+    Network,
+    Unknown,
+}
+
+/// Tells built-in cameras from plugged-in and network ones.
+///
+/// On Windows the enumerator at the start of the device path decides: ACPI
+/// and PCI devices (MIPI sensors behind an image processor) are integrated,
+/// and USB devices are too when firmware describes them as built in. Internal
+/// USB cameras without that description are reported as `Usb`. On Linux a USB parent makes a
+/// device `Usb` and the sensor orientation controls make it `Integrated`;
+/// elsewhere the VID decides between `Usb` and `Unknown`. Paths that are
+/// URLs are `Network`.
+pub fn connection_kind(device: &CameraDevice) -> CameraConnection {
+    let path = device
+        .device_path
+        .as_deref()
+        .unwrap_or_default()
+        .to_lowercase();
+    if ["rtsp://", "rtsps://", "http://", "https://"]
+        .iter()
+        .any(|scheme| path.starts_with(scheme))
+    {
+        return CameraConnection::Network;
+    }
+
+    match device.enumeration_source {
+        EnumerationSource::MediaFoundation
+        | EnumerationSource::DirectShow
+        | EnumerationSource::Registry
+        | EnumerationSource::SetupApi => {
+            let path = path
+                .strip_prefix("\\\\?\\")
+                .or_else(|| path.strip_prefix("\\??\\"))
+                .unwrap_or(&path);
+            match path.split(['#', '\\']).next().unwrap_or_default() {
+                "acpi" | "pci" => CameraConnection::Integrated,
+                "usb" if device.built_in == Some(true) => CameraConnection::Integrated,
+                "usb" => CameraConnection::Usb,
+                _ => CameraConnection::Unknown,
+            }
+        }
+        EnumerationSource::V4l2 if device.vid.is_some() => CameraConnection::Usb,
+        EnumerationSource::V4l2 if device.orientation.is_some() || device.mirrored.is_some() => {
+            CameraConnection::Integrated
+        }
+        _ if device.vid.is_some() => CameraConnection::Usb,
+        _ => CameraConnection::Unknown,
+    }
+}
@@ -0,0 +1,240 @@
+use super::device_enum::{device_instance_key, CameraDevice, EnumerationSource};
+
+pub fn merge_backend_devices(
+    media_foundation: Vec<CameraDevice>,
+    directshow: Vec<CameraDevice>,
+) -> Vec<CameraDevice> {
+    let mut devices = media_foundation;
+    devices.extend(directshow);
+    dedup_camera_devices(devices)
+}
+
+/// Merges records that describe the same physical device.
+///
+/// Records match when their device paths are equal once normalized by
+/// `normalize_device_path`, ignoring the `\\?\` prefix and the trailing
+/// interface GUID. When either record lacks
+/// a path, a record from another backend matches on the same VID/PID pair or,
+/// failing that, the same name ignoring case; differing serial numbers keep
+/// the records apart.
+///
+/// Fields are merged one at a time: the earlier record keeps the values it
+/// has and gains the ones it lacks, except that `manufacturer` and `driver`
+/// from a DirectShow record, which reads them from the driver's own property
+/// bag, replace those another backend derived from the registry, and its
+/// `clsid`, the capture filter's own, replaces the interface CLSID Media
+/// Foundation reports.
+pub fn dedup_camera_devices(devices: Vec<CameraDevice>) -> Vec<CameraDevice> {
+    let mut merged: Vec<(CameraDevice, Vec<EnumerationSource>)> = Vec::new();
+
+    for device in devices {
+        let target = merged
+            .iter()
+            .position(|(existing, sources)| same_device(existing, sources, &device));
+
+        match target {
+            Some(index) => {
+                let (existing, sources) = &mut merged[index];
+                sources.push(device.enumeration_source);
+                merge_fields(existing, device);
+            }
+            None => {
+                let source = device.enumeration_source;
+                merged.push((device, vec![source]));
+            }
+        }
+    }
+
+    merged.into_iter().map(|(device, _)| device).collect()
+}
+
+fn same_device(
+    existing: &CameraDevice,
+    sources: &[EnumerationSource],
+    device: &CameraDevice,
+) -> bool {
+    if let (Some(existing_key), Some(key)) =
+        (device_instance_key(existing), device_instance_key(device))
+    {
+        return existing_key == key;
+    }
+
+    // Without a path to compare, only pair records coming from different backends.
+    if sources.contains(&device.enumeration_source) {
+        return false;
+    }
+    if let (Some(existing_serial), Some(serial)) = (&existing.serial_number, &device.serial_number)
+    {
+        if !existing_serial.eq_ignore_ascii_case(serial) {
+            return false;
+        }
+    }
+
+    match (&existing.vid, &existing.pid, &device.vid, &device.pid) {
+        (Some(existing_vid), Some(existing_pid), Some(vid), Some(pid)) => {
+            existing_vid.eq_ignore_ascii_case(vid) && existing_pid.eq_ignore_ascii_case(pid)
+        }
+        _ => existing.name.eq_ignore_ascii_case(&device.name),
+    }
+}
+
+fn merge_fields(target: &mut CameraDevice, other: CameraDevice) {
+    let prefer_driver_details = other.enumeration_source == EnumerationSource::DirectShow
+        && target.enumeration_source != EnumerationSource::DirectShow;
+
+    merge_field(
+        &mut target.manufacturer,
+        other.manufacturer,
+        prefer_driver_details,
+    );
+    merge_field(&mut target.driver, other.driver, prefer_driver_details);
+    merge_field(&mut target.device_path, other.device_path, false);
+    merge_field(&mut target.vid, other.vid, false);
+    merge_field(&mut target.pid, other.pid, false);
+    merge_field(&mut target.clsid, other.clsid, prefer_driver_details);
+    merge_field(&mut target.serial_number, other.serial_number, false);
+    merge_field(&mut target.orientation, other.orientation, false);
+    merge_field(&mut target.mirrored, other.mirrored, false);
+    merge_field(&mut target.built_in, other.built_in, false);
+    merge_field(&mut target.instance_id, other.instance_id, false);
+    if target.hardware_ids.is_empty() {
+        target.hardware_ids = other.hardware_ids;
+    }
+}
+
+fn merge_field<T>(slot: &mut Option<T>, value: Option<T>, prefer_value: bool) {
+    if prefer_value {
+        if value.is_some() {
+            *slot = value;
+        }
+    } else if slot.is_none() {
+        *slot = value;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PATH: &str =
+        "\\\\?\\usb#vid_046d&pid_085b&mi_00#7&1a2b&0&0000#{e5323777-f976-4f5b-9b55-b94699c46e44}\\global";
+
+    fn device(source: EnumerationSource, path: Option<&str>) -> CameraDevice {
+        CameraDevice {
+            device_path: path.map(String::from),
+            enumeration_source: source,
+            ..CameraDevice::from_name_and_path("Logitech Webcam C925e", PATH)
+        }
+    }
+
+    #[test]
+    fn merges_records_with_the_same_normalized_path() {
+        let upper = PATH.to_uppercase().replace("\\\\?\\", "\\??\\");
+        let devices = vec![
+            device(EnumerationSource::MediaFoundation, Some(PATH)),
+            device(EnumerationSource::DirectShow, Some(&upper)),
+        ];
+
+        assert_eq!(dedup_camera_devices(devices).len(), 1);
+    }
+
+    #[test]
+    fn merges_pathless_records_from_other_backends_on_vid_pid() {
+        let devices = vec![
+            device(EnumerationSource::MediaFoundation, Some(PATH)),
+            device(EnumerationSource::DirectShow, None),
+        ];
+
+        let merged = dedup_camera_devices(devices);
+
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].device_path.as_deref(), Some(PATH));
+    }
+
+    #[test]
+    fn keeps_pathless_records_from_the_same_backend() {
+        let devices = vec![
+            device(EnumerationSource::DirectShow, None),
+            device(EnumerationSource::DirectShow, None),
+        ];
+
+        assert_eq!(dedup_camera_devices(devices).len(), 2);
+    }
+
+    #[test]
+    fn keeps_records_with_different_serials_apart() {
+        let mut directshow = device(EnumerationSource::DirectShow, None);
+        directshow.serial_number = Some("B2".to_string());
+        let mut media_foundation = device(EnumerationSource::MediaFoundation, None);
+        media_foundation.serial_number = Some("A1".to_string());
+
+        assert_eq!(
+            dedup_camera_devices(vec![media_foundation, directshow]).len(),
+            2
+        );
+    }
+
+    #[test]
+    fn keeps_records_built_without_a_path_apart() {
+        let devices = vec![
+            CameraDevice::from_name_and_path("OBS Virtual Camera", ""),
+            CameraDevice::from_name_and_path("Snap Camera", ""),
+        ];
+
+        assert_eq!(dedup_camera_devices(devices).len(), 2);
+    }
+
+    #[test]
+    fn merge_backend_devices_lists_each_camera_once() {
+        let media_foundation = vec![device(EnumerationSource::MediaFoundation, Some(PATH))];
+        let directshow = vec![
+            device(EnumerationSource::DirectShow, Some(PATH)),
+            CameraDevice {
+                enumeration_source: EnumerationSource::DirectShow,
+                ..CameraDevice::from_name_and_path("OBS Virtual Camera", "")
+            },
+        ];
+
+        let names = merge_backend_devices(media_foundation, directshow)
+            .into_iter()
+            .map(|device| device.name)
+            .collect::<Vec<_>>();
+
+        assert_eq!(names, ["Logitech Webcam C925e", "OBS Virtual Camera"]);
+    }
+
+    #[test]
+    fn directshow_details_replace_other_backends() {
+        let media_foundation = CameraDevice {
+            manufacturer: Some("(Standard system devices)".to_string()),
+            clsid: Some("{e5323777-f976-4f5b-9b55-b94699c46e44}".to_string()),
+            orientation: Some(90),
+            ..device(EnumerationSource::MediaFoundation, Some(PATH))
+        };
+        let directshow = CameraDevice {
+            name: "Logitech C925e".to_string(),
+            manufacturer: Some("Logitech".to_string()),
+            driver: Some("usbvideo".to_string()),
+            clsid: Some("{17cca71b-ecd7-11d0-b908-00a0c9223196}".to_string()),
+            orientation: Some(0),
+            ..device(EnumerationSource::DirectShow, Some(PATH))
+        };
+
+        let merged = dedup_camera_devices(vec![media_foundation, directshow]);
+
+        assert_eq!(merged.len(), 1);
+        let merged = &merged[0];
+        assert_eq!(merged.name, "Logitech Webcam C925e");
+        assert_eq!(merged.manufacturer.as_deref(), Some("Logitech"));
+        assert_eq!(merged.driver.as_deref(), Some("usbvideo"));
+        assert_eq!(
+            merged.clsid.as_deref(),
+            Some("{17cca71b-ecd7-11d0-b908-00a0c9223196}")
+        );
+        assert_eq!(merged.orientation, Some(90));
+        assert_eq!(
+            merged.enumeration_source,
+            EnumerationSource::MediaFoundation
+        );
+    }
+}
@@ -0,0 +1,269 @@
+use std::fmt;
+#[cfg(feature = "serde")]
+use std::path::Path;
+use std::sync::Arc;
+
+#[cfg(feature = "serde")]
+use super::config::ConfigParseError;
+use super::config::DetectionConfig;
+use super::device_enum::{
+    classify_device_with, enumerate_devices, summarize, CameraDevice, Classification,
+    DetectionResult,
+};
+use super::enumerator::CameraEnumerator;
+use super::error::EnumerationError;
+#[cfg(feature = "serde")]
+use super::rules::Rules;
+use super::scoring::{score_with, VirtualScore};
+
+/// Enumerates and classifies cameras with a fixed set of rules.
+///
+/// `Detector::default()` uses the built-in rules and the platform's
+/// backends; `with_enumerator` swaps the backends for any
+/// `CameraEnumerator`, such as a `MockEnumerator`.
+#[derive(Clone, Default)]
+pub struct Detector {
+    config: DetectionConfig,
+    enumerator: Option<Arc<dyn CameraEnumerator>>,
+}
+
+impl fmt::Debug for Detector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Detector")
+            .field("config", &self.config)
+            .field("custom_enumerator", &self.enumerator.is_some())
+            .finish()
+    }
+}
+
+impl Detector {
+    pub fn new(config: DetectionConfig) -> Self {
+        Self {
+            config,
+            enumerator: None,
+        }
+    }
+
+    pub fn with_enumerator(mut self, enumerator: Box<dyn CameraEnumerator>) -> Self {
+        self.enumerator = Some(Arc::from(enumerator));
+        self
+    }
+
+    /// A detector using the rules in a TOML or JSON document; see `Rules`.
+    #[cfg(feature = "serde")]
+    pub fn from_rules_str(rules: &str) -> Result<Self, ConfigParseError> {
+        Ok(Self::new(rules.parse::<Rules>()?.into_config()))
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn from_rules_file(path: impl AsRef<Path>) -> Result<Self, ConfigParseError> {
+        Ok(Self::new(Rules::from_file(path)?.into_config()))
+    }
+
+    /// The built-in rules, ready to be written out with `Rules::to_toml` or
+    /// `Rules::to_json`.
+    #[cfg(feature = "serde")]
+    pub fn default_rules() -> Rules {
+        Rules::defaults()
+    }
+
+    pub fn builder() -> DetectorBuilder {
+        DetectorBuilder::default()
+    }
+
+    pub fn config(&self) -> &DetectionConfig {
+        &self.config
+    }
+
+    pub fn enumerate(&self) -> Result<Vec<CameraDevice>, EnumerationError> {
+        match &self.enumerator {
+            Some(enumerator) => enumerator.enumerate(),
+            None => enumerate_devices(),
+        }
+    }
+
+    pub fn classify(&self, device: &CameraDevice) -> Classification {
+        classify_device_with(device, &self.config)
+    }
+
+    /// Scores `device` under this detector's rules and weights. The total
+    /// decides `classify` and `detect` only when the detector has a score
+    /// threshold; see `DetectorBuilder::score_threshold`.
+    pub fn classify_scored(&self, device: &CameraDevice) -> VirtualScore {
+        score_with(device, &self.config)
+    }
+
+    pub fn detect(&self) -> Result<DetectionResult, EnumerationError> {
+        Ok(summarize(self.enumerate()?, &self.config).overall())
+    }
+
+    /// Like `detect`, for devices that were already enumerated.
+    pub fn detect_from(&self, devices: &[CameraDevice]) -> DetectionResult {
+        summarize(devices.to_vec(), &self.config).overall()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DetectorBuilder {
+    detector: Detector,
+}
+
+impl DetectorBuilder {
+    pub fn add_name_keyword(mut self, keyword: &str) -> Self {
+        self.detector.config = self.detector.config.add_name_keyword(keyword);
+        self
+    }
+
+    pub fn add_vid_pid(mut self, vid: &str, pid: &str) -> Self {
+        self.detector.config = self.detector.config.add_vid_pid(vid, pid);
+        self
+    }
+
+    pub fn add_clsid(mut self, clsid: &str) -> Self {
+        self.detector.config = self.detector.config.add_clsid(clsid);
+        self
+    }
+
+    pub fn whitelist_name(mut self, name: &str) -> Self {
+        self.detector.config.whitelist = self.detector.config.whitelist.add_name(name);
+        self
+    }
+
+    pub fn whitelist_vid_pid(mut self, vid: &str, pid: &str) -> Self {
+        self.detector.config.whitelist = self.detector.config.whitelist.add_vid_pid(vid, pid);
+        self
+    }
+
+    pub fn known_real_vid(mut self, vid: &str) -> Self {
+        self.detector.config = self.detector.config.add_known_real_vid(vid);
+        self
+    }
+
+    pub fn strict_hardware_check(mut self, strict_hardware_check: bool) -> Self {
+        self.detector.config = self
+            .detector
+            .config
+            .strict_hardware_check(strict_hardware_check);
+        self
+    }
+
+    /// Classifies devices by the points of `Detector::classify_scored`:
+    /// those scoring at or above `threshold` are virtual. 50 matches the
+    /// default weights.
+    pub fn score_threshold(mut self, threshold: i32) -> Self {
+        self.detector.config = self.detector.config.with_score_threshold(threshold);
+        self
+    }
+
+    pub fn enumerator(mut self, enumerator: Box<dyn CameraEnumerator>) -> Self {
+        self.detector = self.detector.with_enumerator(enumerator);
+        self
+    }
+
+    pub fn build(self) -> Detector {
+        self.detector
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::camera::device_enum::{classify_device, detect_cameras_from, DetectionReason};
+
+    fn usb_camera(name: &str, vid: &str, pid: &str) -> CameraDevice {
+        CameraDevice::from_name_and_path(
+            name,
+            &format!("\\\\?\\usb#vid_{vid}&pid_{pid}#5&1a2b3c&0&1#{{e5323777-f976-4f5b-9b55-b94699c46e44}}\\global"),
+        )
+    }
+
+    #[test]
+    fn builder_rules_extend_the_defaults() {
+        let detector = Detector::builder()
+            .add_name_keyword("finecam")
+            .add_vid_pid("1234", "abcd")
+            .build();
+
+        assert_eq!(
+            detector.classify(&CameraDevice::from_name_and_path("FineCam", "")),
+            Classification::Virtual(DetectionReason::NameKeyword("finecam".to_string()))
+        );
+        assert_eq!(
+            detector.classify(&usb_camera("Camera", "1234", "abcd")),
+            Classification::Virtual(DetectionReason::VidPidMatch {
+                vid: "1234".to_string(),
+                pid: "abcd".to_string(),
+            })
+        );
+        assert!(matches!(
+            detector.classify(&CameraDevice::from_name_and_path("OBS Virtual Camera", "")),
+            Classification::Virtual(_)
+        ));
+    }
+
+    #[test]
+    fn whitelist_beats_the_blacklist() {
+        let detector = Detector::builder()
+            .add_name_keyword("inspection")
+            .whitelist_name("acme virtual inspection cam")
+            .whitelist_vid_pid("0bda", "58f4")
+            .build();
+
+        let acme = usb_camera("ACME Virtual Inspection Cam", "2222", "0001");
+        let obs_ids = usb_camera("Capture", "0bda", "58f4");
+
+        assert_eq!(detector.classify(&acme), Classification::Real);
+        assert_eq!(detector.classify(&obs_ids), Classification::Real);
+        assert_eq!(
+            detector.detect_from(&[acme, obs_ids]),
+            DetectionResult::RealCamera
+        );
+    }
+
+    #[test]
+    fn default_detector_matches_the_free_functions() {
+        let devices = [
+            usb_camera("Logitech HD Pro Webcam C920", "046d", "082d"),
+            usb_camera("Capture", "0bda", "58f4"),
+            CameraDevice::from_name_and_path("OBS Virtual Camera", ""),
+            CameraDevice::from_name_and_path("Snap Camera", ""),
+        ];
+        let detector = Detector::default();
+
+        for device in &devices {
+            assert_eq!(detector.classify(device), classify_device(device));
+        }
+        assert_eq!(
+            detector.detect_from(&devices),
+            detect_cameras_from(&devices)
+        );
+    }
+
+    #[test]
+    fn score_threshold_drives_classify_and_detect() {
+        let device = CameraDevice {
+            clsid: Some("{A3FCE0F5-3493-419F-958A-ABA1250EC20B}".to_string()),
+            ..usb_camera("Logitech Virtual Cam", "046d", "0825")
+        };
+
+        // 60 for the name and 90 for the CLSID, less 70 for the vendor.
+        let scored = Detector::builder().score_threshold(50).build();
+        assert_eq!(scored.classify_scored(&device).total, 80);
+        assert_eq!(
+            scored.classify(&device),
+            Classification::Virtual(DetectionReason::Score(80))
+        );
+        assert_eq!(
+            scored.detect_from(std::slice::from_ref(&device)),
+            DetectionResult::VirtualCamera
+        );
+
+        let strict = Detector::builder().score_threshold(81).build();
+        assert_eq!(strict.classify(&device), Classification::Real);
+
+        // Without a threshold the vendor decides, whatever the total.
+        let detector = Detector::default();
+        assert_eq!(detector.classify(&device), Classification::Real);
+        assert_eq!(detector.classify_scored(&device).threshold, None);
+    }
+}
@@ -1,372 +1,1952 @@
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use super::config::{DetectionConfig, Whitelist};
+use super::diagnosis::{camera_access_status, CameraAccessStatus};
+use super::enumerator::{default_enumerator, enumerate_with, DetectionOptions};
+use super::error::EnumerationError;
+use super::known_hardware::vendor_name;
+use super::platform;
+use super::remote_control::is_remote_control_camera;
+use super::scoring::score_with;
+#[cfg(feature = "tokio")]
+use super::worker::run_on_worker;
+
+/// Equality and hashing identify the physical device rather than compare
+/// every field.
+///
+/// Two devices are equal when both have a device path and the paths match
+/// once normalized by `normalize_device_path`, ignoring the `\\?\` prefix,
+/// the trailing interface GUID and the USB revision (`&rev_xxxx`), so the
+/// Media Foundation and DirectShow records of one camera compare equal.
+/// When neither has a path, the VID/PID pair decides, ignoring case; when
+/// neither has a VID or PID either, the name decides. A device with a path
+/// never equals one without.
+///
+/// With the `serde` feature, `None` fields serialize as `null` rather than
+/// being skipped, so every device has the same shape.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CameraDevice {
     pub name: String,
     pub manufacturer: Option<String>,
     pub device_path: Option<String>,
     pub driver: Option<String>,
+    /// The USB vendor ID in uppercase hex, e.g. `046D`, as Windows tools
+    /// show it. Rules match it ignoring case.
     pub vid: Option<String>,
+    /// The USB product ID in uppercase hex, e.g. `0825`.
     pub pid: Option<String>,
     pub clsid: Option<String>,
+    pub serial_number: Option<String>,
+    /// Clockwise rotation of the sensor in degrees, when the driver reports it.
+    pub orientation: Option<i32>,
+    /// Whether previews should be mirrored, i.e. the camera faces the user.
+    pub mirrored: Option<bool>,
+    /// Whether firmware describes the camera as built into the machine;
+    /// only Windows reads this, from the ACPI _PLD.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub built_in: Option<bool>,
+    /// The PnP hardware IDs, most specific first; only SetupAPI reports them.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub hardware_ids: Vec<String>,
+    /// The Windows device instance ID, e.g. `USB\VID_046D&PID_085B&MI_00\7&1A2B&0&0000`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub instance_id: Option<String>,
+    pub enumeration_source: EnumerationSource,
+}
+
+impl PartialEq for CameraDevice {
+    fn eq(&self, other: &Self) -> bool {
+        match (device_instance_key(self), device_instance_key(other)) {
+            (Some(key), Some(other_key)) => key == other_key,
+            (None, None) => {
+                let ids = (self.vid.is_some() || self.pid.is_some())
+                    || (other.vid.is_some() || other.pid.is_some());
+                if ids {
+                    lowercase_id(&self.vid) == lowercase_id(&other.vid)
+                        && lowercase_id(&self.pid) == lowercase_id(&other.pid)
+                } else {
+                    self.name == other.name
+                }
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Eq for CameraDevice {}
+
+impl Hash for CameraDevice {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match device_instance_key(self) {
+            Some(key) => key.hash(state),
+            None => (lowercase_id(&self.vid), lowercase_id(&self.pid)).hash(state),
+        }
+    }
+}
+
+impl fmt::Display for CameraDevice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (VID:{} PID:{})",
+            self.name,
+            self.vid.as_deref().unwrap_or("?"),
+            self.pid.as_deref().unwrap_or("?")
+        )
+    }
+}
+
+impl CameraDevice {
+    /// A device known only by its Windows device path, with the VID, PID
+    /// and serial number parsed from it and the name set to `"Unknown"`.
+    ///
+    /// The source is reported as Media Foundation, whose symbolic links
+    /// have this form.
+    pub fn from_device_path(path: &str) -> Self {
+        Self::from_name_and_path("Unknown", path)
+    }
+
+    /// A device with the given name, and the VID, PID and serial number
+    /// parsed from `path`. A blank `path` leaves `device_path` unset, so
+    /// such devices are told apart by name rather than all comparing equal.
+    pub fn from_name_and_path(name: &str, path: &str) -> Self {
+        let path = Some(path).filter(|path| !path.trim().is_empty());
+        let (vid, pid) = parse_vid_pid(path);
+        Self {
+            name: name.to_string(),
+            manufacturer: None,
+            device_path: path.map(String::from),
+            driver: None,
+            vid,
+            pid,
+            clsid: None,
+            serial_number: parse_serial_number(path),
+            orientation: None,
+            mirrored: None,
+            built_in: None,
+            hardware_ids: Vec::new(),
+            instance_id: None,
+            enumeration_source: EnumerationSource::MediaFoundation,
+        }
+    }
+
+    /// The VID as a number; `None` when it is missing or not a 16-bit hex
+    /// value.
+    pub fn vendor_id(&self) -> Option<u16> {
+        parse_hex_id(self.vid.as_deref())
+    }
+
+    /// The PID as a number, like `vendor_id`.
+    pub fn product_id(&self) -> Option<u16> {
+        parse_hex_id(self.pid.as_deref())
+    }
+
+    /// The vendor behind the VID, for well-known camera makers.
+    pub fn vendor_name(&self) -> Option<&'static str> {
+        vendor_name(self.vendor_id()?)
+    }
+
+    /// An identifier that stays the same across enumerations and restarts,
+    /// suitable for persisting a choice of camera.
+    ///
+    /// It is the device path normalized the way equality compares it;
+    /// devices without a path get `name:` followed by a hash of the name,
+    /// VID and PID.
+    pub fn stable_id(&self) -> String {
+        if let Some(key) = device_instance_key(self) {
+            return key;
+        }
+
+        // FNV-1a, which unlike `DefaultHasher` is fixed across Rust releases.
+        let mut hash = 0xcbf2_9ce4_8422_2325u64;
+        let input = [
+            self.name.as_str(),
+            &lowercase_id(&self.vid),
+            &lowercase_id(&self.pid),
+        ]
+        .join("\0");
+        for byte in input.bytes() {
+            hash = (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+        format!("name:{hash:016x}")
+    }
+
+    /// Every field on its own labelled line, `?` standing for unknown values.
+    pub fn display_verbose(&self) -> String {
+        let field = |value: &Option<String>| value.as_deref().unwrap_or("?").to_string();
+        [
+            format!("Name: {}", self.name),
+            format!("Manufacturer: {}", field(&self.manufacturer)),
+            format!("Driver: {}", field(&self.driver)),
+            format!("Device path: {}", field(&self.device_path)),
+            format!("CLSID: {}", field(&self.clsid)),
+            format!("VID: {}", field(&self.vid)),
+            format!("PID: {}", field(&self.pid)),
+            format!("Serial number: {}", field(&self.serial_number)),
+            format!(
+                "Orientation: {}",
+                field(&self.orientation.map(|degrees| degrees.to_string()))
+            ),
+            format!(
+                "Mirrored: {}",
+                field(&self.mirrored.map(|mirrored| mirrored.to_string()))
+            ),
+            format!(
+                "Built in: {}",
+                field(&self.built_in.map(|built_in| built_in.to_string()))
+            ),
+            format!("Instance ID: {}", field(&self.instance_id)),
+            format!(
+                "Hardware IDs: {}",
+                field(&(!self.hardware_ids.is_empty()).then(|| self.hardware_ids.join(", ")))
+            ),
+            format!("Source: {:?}", self.enumeration_source),
+        ]
+        .join("\n")
+    }
+}
+
+// `from_str_radix` alone would accept a leading `+`.
+fn parse_hex_id(id: Option<&str>) -> Option<u16> {
+    let id = id.filter(|id| id.chars().all(|c| c.is_ascii_hexdigit()))?;
+    u16::from_str_radix(id, 16).ok()
+}
+
+fn lowercase_id(id: &Option<String>) -> String {
+    id.as_deref().unwrap_or("").to_lowercase()
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+#[non_exhaustive]
+pub enum EnumerationSource {
+    MediaFoundation,
+    DirectShow,
+    V4l2,
+    AvFoundation,
+    /// The kernel streaming device classes in the Windows registry.
+    Registry,
+    /// The camera interface class as listed by SetupAPI.
+    SetupApi,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+#[non_exhaustive]
 pub enum DetectionResult {
     RealCamera,
     VirtualCamera,
     NoCamera,
+    /// Real and virtual cameras are both present.
+    Mixed {
+        real_count: usize,
+        virtual_count: usize,
+    },
+    /// Only network cameras are present; see
+    /// `DetectionConfig::network_cameras_are_real`.
+    NetworkCamera,
 }
 
-pub fn enumerate_devices() -> Vec<CameraDevice> {
-    #[cfg(windows)]
-    {
-        enumerate_windows_devices()
+impl fmt::Display for DetectionResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DetectionResult::RealCamera => write!(f, "Real camera detected"),
+            DetectionResult::VirtualCamera => write!(f, "Virtual camera detected"),
+            DetectionResult::NoCamera => write!(f, "No camera found"),
+            DetectionResult::NetworkCamera => write!(f, "Network camera detected"),
+            DetectionResult::Mixed {
+                real_count,
+                virtual_count,
+            } => write!(
+                f,
+                "Real and virtual cameras detected ({real_count} real, {virtual_count} virtual)"
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DetectionSummary {
+    /// Real cameras, including tethered phones.
+    pub real: Vec<CameraDevice>,
+    #[cfg_attr(feature = "serde", serde(rename = "virtual"))]
+    pub virtual_: Vec<CameraDevice>,
+    /// Network cameras, unless the config counts them as real.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub network: Vec<CameraDevice>,
+    pub total: usize,
+    /// Privacy settings can block a camera that is present; only
+    /// `detect_cameras_detailed` reads them.
+    pub access: CameraAccessStatus,
+}
+
+impl DetectionSummary {
+    pub fn real_count(&self) -> usize {
+        self.real.len()
     }
 
-    #[cfg(not(windows))]
-    {
-        Vec::new()
+    pub fn virtual_count(&self) -> usize {
+        self.virtual_.len()
+    }
+
+    pub fn network_count(&self) -> usize {
+        self.network.len()
+    }
+
+    /// `Mixed` when both kinds are present, so a virtual camera is never
+    /// hidden behind a real one. Network cameras only decide the result
+    /// when there is no other camera.
+    pub fn overall(&self) -> DetectionResult {
+        if !self.real.is_empty() && !self.virtual_.is_empty() {
+            DetectionResult::Mixed {
+                real_count: self.real_count(),
+                virtual_count: self.virtual_count(),
+            }
+        } else if !self.real.is_empty() {
+            DetectionResult::RealCamera
+        } else if !self.virtual_.is_empty() {
+            DetectionResult::VirtualCamera
+        } else if !self.network.is_empty() {
+            DetectionResult::NetworkCamera
+        } else {
+            DetectionResult::NoCamera
+        }
+    }
+}
+
+pub fn enumerate_devices() -> Result<Vec<CameraDevice>, EnumerationError> {
+    enumerate_with(default_enumerator().as_ref())
+}
+
+/// The enumerated devices one at a time, for chaining iterator adapters.
+///
+/// The backends list all devices in one call, which happens here; when
+/// some of them fail, the devices of the others are yielded. Platforms
+/// without a backend yield nothing without enumerating.
+pub fn enumerate_devices_iter() -> impl Iterator<Item = CameraDevice> {
+    enumerate_devices()
+        .unwrap_or_else(EnumerationError::into_partial_devices)
+        .into_iter()
+}
+
+/// The enumerated devices that satisfy `predicate`.
+pub fn enumerate_devices_filtered(predicate: impl Fn(&CameraDevice) -> bool) -> Vec<CameraDevice> {
+    enumerate_devices_iter()
+        .filter(|device| predicate(device))
+        .collect()
+}
+
+/// The enumerated devices as a JSON array.
+#[cfg(feature = "serde")]
+pub fn enumerate_devices_json() -> Result<String, EnumerationError> {
+    let devices = enumerate_devices()?;
+    // Plain structs with string keys always serialize.
+    Ok(serde_json::to_string(&devices).expect("camera devices serialize to JSON"))
+}
+
+/// `enumerate_devices` without merging the records several backends
+/// report for the same device.
+pub fn enumerate_devices_raw() -> Result<Vec<CameraDevice>, EnumerationError> {
+    DetectionOptions::default().composite().enumerate_raw()
+}
+
+pub fn enumerate_devices_from(
+    source: EnumerationSource,
+) -> Result<Vec<CameraDevice>, EnumerationError> {
+    match source {
+        #[cfg(windows)]
+        EnumerationSource::MediaFoundation => {
+            platform::windows::enumerate_media_foundation_devices()
+        }
+        #[cfg(windows)]
+        EnumerationSource::DirectShow => platform::windows::enumerate_directshow_devices(),
+        #[cfg(windows)]
+        EnumerationSource::Registry => Ok(platform::windows_registry::enumerate_registry_devices()),
+        #[cfg(windows)]
+        EnumerationSource::SetupApi => Ok(platform::windows_setupapi::enumerate_setupapi_devices()),
+        #[cfg(target_os = "linux")]
+        EnumerationSource::V4l2 => Ok(platform::linux::enumerate_linux_devices()),
+        #[cfg(target_os = "macos")]
+        EnumerationSource::AvFoundation => Ok(platform::macos::enumerate_macos_devices()),
+        #[allow(unreachable_patterns)]
+        _ => Ok(Vec::new()),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+#[non_exhaustive]
+pub enum DeviceKind {
+    Real,
+    Virtual,
+    /// A real camera on a tethered phone, such as a macOS Continuity Camera.
+    PhoneTether,
+    /// An IP or RTSP camera, such as a DirectShow source filter that streams
+    /// from the network. Neither real nor virtual.
+    Network,
+}
+
+impl DeviceKind {
+    pub fn is_real(self) -> bool {
+        matches!(self, DeviceKind::Real | DeviceKind::PhoneTether)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+#[non_exhaustive]
+pub enum Classification {
+    Real,
+    Virtual(DetectionReason),
+}
+
+/// Why a device was classified the way it was; every variant other than
+/// `Real` names the rule that flagged it and the value that matched.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+#[non_exhaustive]
+pub enum DetectionReason {
+    Real,
+    NameKeyword(String),
+    VidPidMatch {
+        vid: String,
+        pid: String,
+    },
+    ClsidMatch(String),
+    RemoteControl,
+    MissingManufacturer,
+    /// The driver service is a known software driver; see
+    /// `DetectionConfig::driver_services`.
+    DriverService(String),
+    /// The device path is not on a hardware bus; see
+    /// `DetectionConfig::strict_hardware_check`.
+    NoHardwarePath,
+    PlaceholderSerial(String),
+    /// Another device with a different instance reports the same serial.
+    DuplicateSerial(String),
+    /// The points of `classify_scored` reached
+    /// `DetectionConfig::score_threshold`; holds the total.
+    Score(i32),
+}
+
+impl DetectionReason {
+    pub fn is_virtual(&self) -> bool {
+        *self != DetectionReason::Real
+    }
+}
+
+pub fn classify_devices() -> Vec<(CameraDevice, DeviceKind)> {
+    classify_with(&DetectionConfig::default())
+}
+
+pub fn classify_devices_with_reason() -> Vec<(CameraDevice, DetectionReason)> {
+    classify_reasons(
+        enumerate_devices().unwrap_or_else(EnumerationError::into_partial_devices),
+        &DetectionConfig::default(),
+    )
+}
+
+pub fn enumerate_devices_with_whitelist(whitelist: &Whitelist) -> Vec<(CameraDevice, DeviceKind)> {
+    classify_with(&DetectionConfig {
+        whitelist: whitelist.clone(),
+        ..DetectionConfig::default()
+    })
+}
+
+pub fn classify_with(config: &DetectionConfig) -> Vec<(CameraDevice, DeviceKind)> {
+    classify_all(
+        enumerate_devices().unwrap_or_else(EnumerationError::into_partial_devices),
+        config,
+    )
+}
+
+/// The real cameras, tethered phones included.
+pub fn enumerate_real_cameras() -> Vec<CameraDevice> {
+    enumerate_real_cameras_with(&DetectionConfig::default())
+}
+
+pub fn enumerate_real_cameras_with(config: &DetectionConfig) -> Vec<CameraDevice> {
+    classify_with(config)
+        .into_iter()
+        .filter(|(_, kind)| kind.is_real())
+        .map(|(device, _)| device)
+        .collect()
+}
+
+pub fn enumerate_virtual_cameras() -> Vec<CameraDevice> {
+    enumerate_virtual_cameras_with(&DetectionConfig::default())
+}
+
+pub fn enumerate_virtual_cameras_with(config: &DetectionConfig) -> Vec<CameraDevice> {
+    classify_with(config)
+        .into_iter()
+        .filter(|(_, kind)| *kind == DeviceKind::Virtual)
+        .map(|(device, _)| device)
+        .collect()
+}
+
+/// The kind of `device` under the built-in rules, for devices that were
+/// not enumerated by this crate.
+///
+/// The device is judged on its own, like `classify_device` does.
+pub fn classify(device: &CameraDevice) -> DeviceKind {
+    let config = DetectionConfig::default();
+    let reason = device_reason(device, std::slice::from_ref(device), &config);
+    device_kind(device, &reason, &config)
+}
+
+pub(crate) fn classify_all(
+    devices: Vec<CameraDevice>,
+    config: &DetectionConfig,
+) -> Vec<(CameraDevice, DeviceKind)> {
+    classify_reasons(devices, config)
+        .into_iter()
+        .map(|(device, reason)| {
+            let kind = device_kind(&device, &reason, config);
+            #[cfg(feature = "tracing")]
+            tracing::debug!(name = %device.name, ?kind, ?reason, "classified device");
+            (device, kind)
+        })
+        .collect()
+}
+
+fn device_kind(
+    device: &CameraDevice,
+    reason: &DetectionReason,
+    config: &DetectionConfig,
+) -> DeviceKind {
+    if reason.is_virtual() {
+        DeviceKind::Virtual
+    } else if !config.network_cameras_are_real && has_network_marker(device, config) {
+        DeviceKind::Network
+    } else if is_phone_tether(device) {
+        DeviceKind::PhoneTether
+    } else {
+        DeviceKind::Real
     }
 }
 
+fn classify_reasons(
+    devices: Vec<CameraDevice>,
+    config: &DetectionConfig,
+) -> Vec<(CameraDevice, DetectionReason)> {
+    let reasons = devices
+        .iter()
+        .map(|device| device_reason(device, &devices, config))
+        .collect::<Vec<_>>();
+
+    devices.into_iter().zip(reasons).collect()
+}
+
+/// Classifies devices lazily, in order, yielding `true` for virtual ones.
+///
+/// Each device is judged on its own, so a serial shared between devices is
+/// not flagged the way `classify_with` flags it; placeholder serials are.
+pub fn classify_stream<'a>(
+    devices: impl IntoIterator<Item = CameraDevice> + 'a,
+    config: &'a DetectionConfig,
+) -> impl Iterator<Item = (CameraDevice, bool)> + 'a {
+    devices.into_iter().map(move |device| {
+        let is_virtual = device_reason(&device, std::slice::from_ref(&device), config).is_virtual();
+        (device, is_virtual)
+    })
+}
+
+/// `enumerate_devices` on a dedicated thread that keeps COM and Media
+/// Foundation initialized between calls, so the executor never blocks.
+#[cfg(feature = "tokio")]
+pub async fn enumerate_devices_async() -> Result<Vec<CameraDevice>, EnumerationError> {
+    run_on_worker(enumerate_devices).await
+}
+
+/// `detect_cameras_checked` on the thread `enumerate_devices_async` uses.
+#[cfg(feature = "tokio")]
+pub async fn detect_cameras_async() -> Result<DetectionResult, EnumerationError> {
+    run_on_worker(detect_cameras_checked).await
+}
+
+pub fn detect_cameras_checked() -> Result<DetectionResult, EnumerationError> {
+    detect_cameras_with(&DetectionConfig::default())
+}
+
+pub fn detect_cameras_with(config: &DetectionConfig) -> Result<DetectionResult, EnumerationError> {
+    Ok(summarize(enumerate_devices()?, config).overall())
+}
+
+/// Whether any enumerated device is virtual under the built-in rules.
+///
+/// Classification stops at the first virtual device. When some backends
+/// fail, the devices of the others are checked.
+pub fn has_virtual_camera() -> bool {
+    has_virtual_camera_with_config(&DetectionConfig::default())
+}
+
+pub fn has_virtual_camera_with_config(config: &DetectionConfig) -> bool {
+    let devices = enumerate_devices().unwrap_or_else(EnumerationError::into_partial_devices);
+    any_virtual(&devices, config)
+}
+
+/// `has_virtual_camera` over devices enumerated some other way.
+pub fn has_virtual_camera_from(devices: &[CameraDevice]) -> bool {
+    any_virtual(devices, &DetectionConfig::default())
+}
+
+fn any_virtual(devices: &[CameraDevice], config: &DetectionConfig) -> bool {
+    devices
+        .iter()
+        .any(|device| device_reason(device, devices, config).is_virtual())
+}
+
+/// Applies the default rules to `devices` without enumerating anything.
+pub fn detect_cameras_from(devices: &[CameraDevice]) -> DetectionResult {
+    summarize(devices.to_vec(), &DetectionConfig::default()).overall()
+}
+
+/// Like `detect_cameras_checked`, but keeps the devices behind the result.
+pub fn detect_cameras_detailed() -> Result<DetectionSummary, EnumerationError> {
+    Ok(DetectionSummary {
+        access: camera_access_status(),
+        ..summarize(enumerate_devices()?, &DetectionConfig::default())
+    })
+}
+
+pub(crate) fn summarize(devices: Vec<CameraDevice>, config: &DetectionConfig) -> DetectionSummary {
+    let mut summary = DetectionSummary {
+        total: devices.len(),
+        ..DetectionSummary::default()
+    };
+    for (device, kind) in classify_all(devices, config) {
+        match kind {
+            DeviceKind::Network => summary.network.push(device),
+            kind if kind.is_real() => summary.real.push(device),
+            _ => summary.virtual_.push(device),
+        }
+    }
+    summary
+}
+
+#[deprecated(note = "use `detect_cameras_checked`, which reports enumeration failures")]
 pub fn detect_cameras() -> DetectionResult {
-    let devices = enumerate_devices();
+    detect_cameras_checked().unwrap_or(DetectionResult::NoCamera)
+}
 
-    if devices.is_empty() {
-        return DetectionResult::NoCamera;
+pub fn classify_device(device: &CameraDevice) -> Classification {
+    classify_device_with(device, &DetectionConfig::default())
+}
+
+pub(crate) fn classify_device_with(
+    device: &CameraDevice,
+    config: &DetectionConfig,
+) -> Classification {
+    match device_reason(device, std::slice::from_ref(device), config) {
+        DetectionReason::Real => Classification::Real,
+        reason => Classification::Virtual(reason),
     }
+}
+
+/// The built-in rule that flags `device` as virtual, if any.
+///
+/// Unlike `classify_device`, serial numbers are not considered.
+pub fn classify_reason(device: &CameraDevice) -> Option<DetectionReason> {
+    Some(detection_reason(device, &DetectionConfig::default())).filter(DetectionReason::is_virtual)
+}
+
+/// Whether a built-in rule flags `device` as virtual; see `classify_reason`.
+/// Network cameras are not virtual; see `is_network_camera`.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip(device), fields(name = %device.name), ret)
+)]
+pub fn is_virtual_camera(device: &CameraDevice) -> bool {
+    classify_reason(device).is_some()
+}
 
-    let mut has_real = false;
-    let mut has_virtual = false;
+/// Whether `device` looks like an IP or RTSP camera under the built-in
+/// network markers.
+pub fn is_network_camera(device: &CameraDevice) -> bool {
+    has_network_marker(device, &DetectionConfig::default())
+}
 
-    for device in &devices {
-        if is_virtual_camera(device) {
-            has_virtual = true;
+// The rules first, then the serial number checks against `devices`.
+fn device_reason(
+    device: &CameraDevice,
+    devices: &[CameraDevice],
+    config: &DetectionConfig,
+) -> DetectionReason {
+    let reason = detection_reason(device, config);
+    let reason = if reason.is_virtual() || is_allowlisted(device, config) {
+        reason
+    } else {
+        let serial = device.serial_number.clone().unwrap_or_default();
+        if has_placeholder_serial(device) {
+            DetectionReason::PlaceholderSerial(serial)
+        } else if has_suspicious_serial(device, devices) {
+            DetectionReason::DuplicateSerial(serial)
         } else {
-            has_real = true;
+            DetectionReason::Real
         }
+    };
+    if reason.is_virtual() {
+        log::debug!("{:?} is virtual: {reason:?}", device.name);
     }
+    reason
+}
 
-    if has_real {
-        DetectionResult::RealCamera
-    } else if has_virtual {
-        DetectionResult::VirtualCamera
-    } else {
-        DetectionResult::NoCamera
+const NAME_KEYWORD_WEIGHT: f32 = 0.7;
+// Below the default threshold: a manufacturer keyword only adds weight. The
+// OBS camera extension on macOS is still flagged through its model ID.
+const MANUFACTURER_KEYWORD_WEIGHT: f32 = 0.6;
+const DRIVER_KEYWORD_WEIGHT: f32 = 0.5;
+// See `driver_identifies_device`.
+const IDENTIFYING_DRIVER_KEYWORD_WEIGHT: f32 = 0.95;
+// Paths only match whole segments, and a software device's own segment
+// (e.g. `root#manycam#0000`) names it as reliably as its display name.
+const PATH_KEYWORD_WEIGHT: f32 = 0.7;
+const CLSID_WEIGHT: f32 = 0.9;
+const VID_PID_WEIGHT: f32 = 0.95;
+const DRIVER_SERVICE_WEIGHT: f32 = 0.95;
+
+/// How likely `device` is to be virtual, from 0.0 (real) to 1.0, taken
+/// from the strongest rule in `config` that matches it.
+///
+/// Serial numbers are not considered.
+pub fn score_device(device: &CameraDevice, config: &DetectionConfig) -> f32 {
+    strongest_signal(device, config).map_or(0.0, |(score, _)| score)
+}
+
+fn detection_reason(device: &CameraDevice, config: &DetectionConfig) -> DetectionReason {
+    match config.score_threshold {
+        Some(_) => score_with(device, config).reason,
+        None => rule_reason(device, config),
     }
 }
 
-fn is_virtual_camera(device: &CameraDevice) -> bool {
-    let mut haystack = String::new();
-    haystack.push_str(&device.name.to_lowercase());
-    if let Some(value) = &device.manufacturer {
-        haystack.push_str(&value.to_lowercase());
+pub(crate) fn rule_reason(device: &CameraDevice, config: &DetectionConfig) -> DetectionReason {
+    match strongest_signal(device, config) {
+        Some((score, reason)) if score >= config.virtual_threshold => reason,
+        _ => DetectionReason::Real,
     }
-    if let Some(value) = &device.driver {
-        haystack.push_str(&value.to_lowercase());
+}
+
+fn strongest_signal(
+    device: &CameraDevice,
+    config: &DetectionConfig,
+) -> Option<(f32, DetectionReason)> {
+    if is_allowlisted(device, config) {
+        return None;
     }
-    if let Some(value) = &device.device_path {
-        haystack.push_str(&value.to_lowercase());
+
+    // A VID/PID rule names the exact device, so it beats the vendor. The
+    // vendor only counts on a USB path, which a software device that copies
+    // a real VID into its name or registry data does not have.
+    let vid_pid_rule = matching_vid_pid_rule(device, config);
+    if vid_pid_rule.is_none() && has_known_real_vid(device, config) && has_usb_path(device) {
+        return None;
     }
 
-    let name_blacklist = [
-        "virtual",
-        "obs",
-        "manycam",
-        "snap camera",
-        "xsplit",
-        "mmhmm",
-        "droidcam",
-        "iriun",
-        "contacam",
-        "streamlabs",
-        "camsip",
-    ];
+    if is_remote_control_camera(device) {
+        return Some((1.0, DetectionReason::RemoteControl));
+    }
 
-    if name_blacklist.iter().any(|needle| haystack.contains(needle)) {
-        return true;
+    if config.strict && device.manufacturer.is_none() {
+        return Some((1.0, DetectionReason::MissingManufacturer));
+    }
+
+    if config.strict_hardware_check && !has_hardware_path(device) {
+        return Some((1.0, DetectionReason::NoHardwarePath));
     }
 
-    let clsid_blacklist = [
-        "{860bb310-5d01-11d0-bd3b-00a0c911ce86}", // CLSID_VideoInputDeviceCategory
-        "{e5323777-f976-4f5b-9b55-b94699c46e44}", // CLSID_SampleGrabber (often virtual filters)
+    let mut signals = Vec::new();
+
+    // Each field is matched on its own so a keyword cannot straddle two of
+    // them; device paths only match whole segments.
+    let driver_weight = if driver_identifies_device(device) {
+        IDENTIFYING_DRIVER_KEYWORD_WEIGHT
+    } else {
+        DRIVER_KEYWORD_WEIGHT
+    };
+    let fields = [
+        (Some(&device.name), NAME_KEYWORD_WEIGHT, false),
+        (
+            device.manufacturer.as_ref(),
+            MANUFACTURER_KEYWORD_WEIGHT,
+            false,
+        ),
+        (device.driver.as_ref(), driver_weight, false),
+        (device.device_path.as_ref(), PATH_KEYWORD_WEIGHT, true),
     ];
+    for (value, weight, is_path) in fields {
+        let Some(value) = value else {
+            continue;
+        };
+        let field = config.fold_case(value);
+        if let Some(keyword) = config
+            .name_keywords
+            .iter()
+            .find(|needle| keyword_matches(&field, &config.fold_case(needle), is_path))
+        {
+            signals.push((weight, DetectionReason::NameKeyword(keyword.clone())));
+        }
+    }
 
     if let Some(clsid) = &device.clsid {
         let clsid_lower = clsid.to_lowercase();
-        if clsid_blacklist.iter().any(|needle| clsid_lower.contains(needle)) {
-            return true;
+        if let Some(rule) = config
+            .clsids
+            .iter()
+            .find(|needle| clsid_lower.contains(&needle.to_lowercase()))
+        {
+            signals.push((CLSID_WEIGHT, DetectionReason::ClsidMatch(rule.clone())));
         }
     }
 
-    let vid_pid_blacklist = [
-        ("0bda", "58f4"), // OBS Virtual Camera
-        ("0c45", "6366"), // ManyCam Virtual Webcam
-        ("2b7e", "f13a"), // Snap Camera
-        ("05a3", "9331"), // DroidCam
-    ];
-
-    if let (Some(vid), Some(pid)) = (&device.vid, &device.pid) {
-        let vid_lower = vid.to_lowercase();
-        let pid_lower = pid.to_lowercase();
-        if vid_pid_blacklist
+    // Unlike the friendly name, the service name is neither localized nor
+    // chosen by the user.
+    if let Some(service) = device.driver.as_deref().and_then(|driver| {
+        config
+            .driver_services
             .iter()
-            .any(|(v, p)| *v == vid_lower && *p == pid_lower)
-        {
-            return true;
+            .find(|service| service.eq_ignore_ascii_case(driver))
+    }) {
+        signals.push((
+            DRIVER_SERVICE_WEIGHT,
+            DetectionReason::DriverService(service.clone()),
+        ));
+    }
+
+    if let Some((vid, pid)) = vid_pid_rule {
+        signals.push((
+            VID_PID_WEIGHT,
+            DetectionReason::VidPidMatch {
+                vid: vid.clone(),
+                pid: pid.clone(),
+            },
+        ));
+    }
+
+    // Signals are combined by taking the strongest; on a tie the earlier wins.
+    signals.into_iter().reduce(|strongest, signal| {
+        if signal.0 > strongest.0 {
+            signal
+        } else {
+            strongest
         }
+    })
+}
+
+// On V4L2 the driver is the kernel module (e.g. "v4l2 loopback") and on
+// macOS the model ID, which names the DAL plugin or camera extension; either
+// identifies the device as reliably as its VID/PID. Elsewhere it is a vendor's
+// service name, which bundled software can share with real cameras.
+pub(crate) fn driver_identifies_device(device: &CameraDevice) -> bool {
+    matches!(
+        device.enumeration_source,
+        EnumerationSource::V4l2 | EnumerationSource::AvFoundation
+    )
+}
+
+pub(crate) fn has_known_real_vid(device: &CameraDevice, config: &DetectionConfig) -> bool {
+    device.vid.as_deref().is_some_and(|vid| {
+        config
+            .known_real_vids
+            .iter()
+            .any(|known| known.eq_ignore_ascii_case(vid))
+    })
+}
+
+// Integrated MIPI sensors are described by ACPI, so they count as hardware;
+// `root`, `sw` and `swd` devices, and DirectShow filters without a path, do
+// not. Only Windows paths have this shape; other sources always pass.
+pub(crate) fn has_hardware_path(device: &CameraDevice) -> bool {
+    const HARDWARE_ENUMERATORS: [&str; 3] = ["usb", "pci", "acpi"];
+
+    !has_windows_path_shape(device)
+        || path_enumerator(device)
+            .is_some_and(|enumerator| HARDWARE_ENUMERATORS.contains(&&*enumerator))
+}
+
+// Like `has_hardware_path`, but only USB counts. On other platforms the VID
+// itself comes from the USB descriptors.
+pub(crate) fn has_usb_path(device: &CameraDevice) -> bool {
+    !has_windows_path_shape(device) || path_enumerator(device).as_deref() == Some("usb")
+}
+
+fn has_windows_path_shape(device: &CameraDevice) -> bool {
+    matches!(
+        device.enumeration_source,
+        EnumerationSource::MediaFoundation
+            | EnumerationSource::DirectShow
+            | EnumerationSource::Registry
+            | EnumerationSource::SetupApi
+    )
+}
+
+// Interface paths start with the enumerator that created the device.
+fn path_enumerator(device: &CameraDevice) -> Option<String> {
+    let path = device.device_path.as_deref()?.to_lowercase();
+    let path = path
+        .strip_prefix("\\\\?\\")
+        .or_else(|| path.strip_prefix("\\??\\"))
+        .unwrap_or(&path);
+    path.split(['#', '\\']).next().map(str::to_string)
+}
+
+pub(crate) fn matching_vid_pid_rule<'a>(
+    device: &CameraDevice,
+    config: &'a DetectionConfig,
+) -> Option<&'a (String, String)> {
+    let vid_lower = device.vid.as_deref().map(str::to_lowercase);
+    let pid_lower = device.pid.as_deref().map(str::to_lowercase);
+    if vid_lower.is_none() && pid_lower.is_none() {
+        return None;
     }
+    config.vid_pids.iter().find(|(v, p)| {
+        id_matches(&v.to_lowercase(), vid_lower.as_deref())
+            && id_matches(&p.to_lowercase(), pid_lower.as_deref())
+    })
+}
 
-    false
+fn has_network_marker(device: &CameraDevice, config: &DetectionConfig) -> bool {
+    let fields = [
+        Some(&device.name),
+        device.manufacturer.as_ref(),
+        device.device_path.as_ref(),
+    ];
+    fields.into_iter().flatten().any(|field| {
+        let field = field.to_lowercase();
+        config
+            .network_markers
+            .iter()
+            .any(|marker| field.contains(&marker.to_lowercase()))
+    })
 }
 
-#[cfg(windows)]
-fn enumerate_windows_devices() -> Vec<CameraDevice> {
-    let mut devices = enumerate_media_foundation_devices();
-    let mut directshow_devices = enumerate_directshow_devices();
-    devices.append(&mut directshow_devices);
-    devices
+// Continuity Cameras carry the phone's model ID (e.g. `iPhone15,2`) and a
+// name like "Jane's iPhone Camera".
+fn is_phone_tether(device: &CameraDevice) -> bool {
+    if device.enumeration_source != EnumerationSource::AvFoundation {
+        return false;
+    }
+
+    let is_phone = |value: &str| {
+        let value = value.to_lowercase();
+        value.starts_with("iphone") || value.starts_with("ipad")
+    };
+    device.driver.as_deref().is_some_and(is_phone) || device.name.to_lowercase().contains("iphone")
 }
 
-#[cfg(windows)]
-fn enumerate_media_foundation_devices() -> Vec<CameraDevice> {
-    use windows::Win32::Media::MediaFoundation::{
-        MFCreateAttributes, MFEnumDeviceSources, MFShutdown, MFStartup,
-        MF_DEVSOURCE_ATTRIBUTE_FRIENDLY_NAME,
-        MF_DEVSOURCE_ATTRIBUTE_SOURCE_TYPE,
-        MF_DEVSOURCE_ATTRIBUTE_SOURCE_TYPE_VIDCAP_GUID,
-        MF_DEVSOURCE_ATTRIBUTE_SOURCE_TYPE_VIDCAP_SYMBOLIC_LINK, MF_VERSION,
+pub(crate) fn is_allowlisted(device: &CameraDevice, config: &DetectionConfig) -> bool {
+    let whitelist = &config.whitelist;
+    let name = config.fold_case(&device.name);
+    if whitelist
+        .names
+        .iter()
+        .any(|entry| name.contains(&config.fold_case(entry)))
+    {
+        return true;
+    }
+
+    // Unlike the blacklist, a whitelisted pair needs both ids to be known.
+    let (Some(vid), Some(pid)) = (&device.vid, &device.pid) else {
+        return false;
     };
-    use windows::Win32::System::Com::{CoInitializeEx, CoUninitialize, COINIT_MULTITHREADED};
+    whitelist.vid_pids.iter().any(|(v, p)| {
+        (v == "*" || v.eq_ignore_ascii_case(vid)) && (p == "*" || p.eq_ignore_ascii_case(pid))
+    })
+}
 
-    let mut devices = Vec::new();
+const SHORT_KEYWORD_LEN: usize = 5;
 
-    unsafe {
-        if CoInitializeEx(None, COINIT_MULTITHREADED).is_err() {
-            return devices;
-        }
-        if MFStartup(MF_VERSION, 0).is_err() {
-            CoUninitialize();
-            return devices;
-        }
+// Short single-word keywords such as "obs" only match a whole token, so
+// "Kobson Webcam" is not flagged; longer or multi-word keywords match as
+// substrings. `whole_tokens` extends token matching to every single-word
+// keyword.
+pub(crate) fn keyword_matches(field: &str, keyword: &str, whole_tokens: bool) -> bool {
+    let is_single_word = keyword.chars().all(char::is_alphanumeric);
+    if is_single_word && (whole_tokens || keyword.chars().count() <= SHORT_KEYWORD_LEN) {
+        field
+            .split(|c: char| !c.is_alphanumeric())
+            .any(|token| token == keyword)
+    } else {
+        field.contains(keyword)
+    }
+}
 
-        let mut attributes = None;
-        if MFCreateAttributes(&mut attributes, 1).is_err() {
-            MFShutdown().ok();
-            CoUninitialize();
-            return devices;
-        }
-        let attributes = attributes.unwrap();
-        if attributes
-            .SetGUID(
-                &MF_DEVSOURCE_ATTRIBUTE_SOURCE_TYPE,
-                &MF_DEVSOURCE_ATTRIBUTE_SOURCE_TYPE_VIDCAP_GUID,
-            )
-            .is_err()
-        {
-            MFShutdown().ok();
-            CoUninitialize();
-            return devices;
-        }
+// A `*` rule matches any id, including one that failed to parse, so a
+// partial parse matches the single-id rules written with a wildcard. A
+// missing id never matches a concrete rule; accepting it would flag every
+// device with that VID, or that PID, as virtual.
+fn id_matches(rule: &str, value: Option<&str>) -> bool {
+    rule == "*" || value == Some(rule)
+}
 
-        let mut activates = None;
-        let mut count = 0;
-        if MFEnumDeviceSources(&attributes, &mut activates, &mut count).is_ok() {
-            if let Some(activates) = activates {
-                for index in 0..count {
-                    if let Some(activate) = activates.get(index as usize) {
-                        let name = get_activate_string(&activate, &MF_DEVSOURCE_ATTRIBUTE_FRIENDLY_NAME)
-                            .unwrap_or_else(|| "Unknown Camera".to_string());
-                        let device_path = get_activate_string(
-                            &activate,
-                            &MF_DEVSOURCE_ATTRIBUTE_SOURCE_TYPE_VIDCAP_SYMBOLIC_LINK,
-                        );
-                        let (vid, pid) = parse_vid_pid(device_path.as_deref());
-
-                        let device = CameraDevice {
-                            name,
-                            manufacturer: None,
-                            device_path,
-                            driver: None,
-                            vid,
-                            pid,
-                            clsid: None,
-                        };
-                        devices.push(device);
-                    }
-                }
+const PLACEHOLDER_SERIALS: [&str; 12] = [
+    "0",
+    "0000",
+    "00000000",
+    "000000000000",
+    "0123456789",
+    "123456789",
+    "0123456789abcdef",
+    "ffffffff",
+    "none",
+    "null",
+    "default",
+    "serial",
+];
+
+fn has_placeholder_serial(device: &CameraDevice) -> bool {
+    device.serial_number.as_deref().is_some_and(|serial| {
+        let serial = serial.trim().to_lowercase();
+        serial.is_empty() || PLACEHOLDER_SERIALS.contains(&serial.as_str())
+    })
+}
+
+fn has_suspicious_serial(device: &CameraDevice, devices: &[CameraDevice]) -> bool {
+    let Some(serial) = device.serial_number.as_deref() else {
+        return false;
+    };
+    if has_placeholder_serial(device) {
+        return true;
+    }
+
+    let serial = serial.trim().to_lowercase();
+    let Some(instance) = device_instance_key(device) else {
+        return false;
+    };
+
+    devices.iter().any(|other| {
+        other
+            .serial_number
+            .as_deref()
+            .is_some_and(|value| value.trim().to_lowercase() == serial)
+            && device_instance_key(other).is_some_and(|key| key != instance)
+    })
+}
+
+/// A device path in the form used to compare paths across backends.
+///
+/// The path is lowercased and every run of `\` and `/` becomes a single
+/// `\`. A trailing `#0` segment and an `&mi_00` token ending a segment are
+/// dropped; DirectShow adds them to some paths Media Foundation reports
+/// without.
+pub fn normalize_device_path(path: &str) -> String {
+    let mut normalized = String::with_capacity(path.len());
+    for c in path.to_lowercase().chars() {
+        if c == '/' || c == '\\' {
+            if !normalized.ends_with('\\') {
+                normalized.push('\\');
             }
+        } else {
+            normalized.push(c);
         }
+    }
 
-        MFShutdown().ok();
-        CoUninitialize();
+    let mut segments = normalized.split('#').collect::<Vec<_>>();
+    if segments.len() > 1 && segments.last() == Some(&"0") {
+        segments.pop();
     }
+    segments
+        .iter()
+        .map(|segment| segment.strip_suffix("&mi_00").unwrap_or(segment))
+        .collect::<Vec<_>>()
+        .join("#")
+}
 
-    devices
+pub(crate) fn device_instance_key(device: &CameraDevice) -> Option<String> {
+    let path = normalize_device_path(device.device_path.as_deref()?);
+    let path = path
+        .strip_prefix("\\?\\")
+        .or_else(|| path.strip_prefix("\\??\\"))
+        .unwrap_or(&path);
+    let mut segments = path.split('#').collect::<Vec<_>>();
+    if segments.len() > 1 && segments.last().is_some_and(|last| last.starts_with('{')) {
+        segments.pop();
+    }
+    // Firmware updates can change the revision, not the device.
+    let segments = segments
+        .iter()
+        .map(|segment| {
+            segment
+                .split('&')
+                .filter(|token| !token.starts_with("rev_"))
+                .collect::<Vec<_>>()
+                .join("&")
+        })
+        .collect::<Vec<_>>();
+    Some(segments.join("#"))
 }
 
-#[cfg(windows)]
-fn enumerate_directshow_devices() -> Vec<CameraDevice> {
-    use windows::core::Interface;
-    use windows::Win32::Media::DirectShow::{
-        CLSID_SystemDeviceEnum, CLSID_VideoInputDeviceCategory, ICreateDevEnum,
-        IEnumMoniker, IMoniker,
+pub(crate) fn parse_vid_pid(device_path: Option<&str>) -> (Option<String>, Option<String>) {
+    let Some(device_path) = device_path else {
+        return (None, None);
     };
-    use windows::Win32::System::Com::{
-        CoCreateInstance, CoInitializeEx, CoUninitialize, CLSCTX_INPROC_SERVER,
-        COINIT_MULTITHREADED,
+
+    let device_path_lower = device_path.to_lowercase();
+    let (vid, vid_end) = match extract_segment(&device_path_lower, "vid_") {
+        Some((vid, end)) => (Some(vid), end),
+        None => (None, 0),
     };
+    // Composite devices can repeat the tokens later in the path; the PID is
+    // the one that follows the VID.
+    let pid = extract_segment(&device_path_lower[vid_end..], "pid_").map(|(pid, _)| pid);
+    if vid.is_none() || pid.is_none() {
+        log::trace!("incomplete VID/PID ({vid:?}, {pid:?}) in {device_path}");
+    }
+    (
+        vid.map(|vid| vid.to_uppercase()),
+        pid.map(|pid| pid.to_uppercase()),
+    )
+}
+
+// The id runs up to the next `&`, `#` or `\`, so longer ids and trailing
+// revision data (`&pid_0825&rev_0010`) are handled; ids shorter than the
+// four digits USB uses are rejected as truncated. Also returns where the id
+// ends in `source`.
+fn extract_segment(source: &str, token: &str) -> Option<(String, usize)> {
+    let start = source.find(token)? + token.len();
+    let segment = source[start..]
+        .split(['&', '#', '\\'])
+        .next()
+        .unwrap_or_default();
+    if segment.len() >= 4 && segment.chars().all(|c| c.is_ascii_hexdigit()) {
+        Some((segment.to_string(), start + segment.len()))
+    } else {
+        None
+    }
+}
+
+pub(crate) fn parse_serial_number(device_path: Option<&str>) -> Option<String> {
+    let device_path = device_path?;
+    let mut segments = device_path.split('#');
+    segments.find(|segment| segment.to_lowercase().contains("vid_"))?;
+    let segment = segments.next()?;
+
+    if segment.is_empty() || segment.starts_with('{') || segment.contains('&') {
+        return None;
+    }
+    Some(segment.to_string())
+}
 
-    let mut devices = Vec::new();
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    unsafe {
-        if CoInitializeEx(None, COINIT_MULTITHREADED).is_err() {
-            return devices;
+    fn macos_device(name: &str, manufacturer: &str) -> CameraDevice {
+        CameraDevice {
+            manufacturer: Some(manufacturer.to_string()),
+            enumeration_source: EnumerationSource::AvFoundation,
+            ..CameraDevice::from_name_and_path(name, "")
         }
+    }
 
-        let enumerator: ICreateDevEnum = match CoCreateInstance(
-            &CLSID_SystemDeviceEnum,
-            None,
-            CLSCTX_INPROC_SERVER,
-        ) {
-            Ok(enumerator) => enumerator,
-            Err(_) => {
-                CoUninitialize();
-                return devices;
+    const KSCATEGORY_VIDEO_CAMERA: &str = "{e5323777-f976-4f5b-9b55-b94699c46e44}";
+
+    fn usb_device(name: &str, vid_pid: &str, serial: &str) -> CameraDevice {
+        CameraDevice::from_name_and_path(
+            name,
+            &format!("\\\\?\\usb#{vid_pid}#{serial}#{KSCATEGORY_VIDEO_CAMERA}\\global"),
+        )
+    }
+
+    fn reasons(devices: Vec<CameraDevice>) -> Vec<DetectionReason> {
+        classify_reasons(devices, &DetectionConfig::default())
+            .into_iter()
+            .map(|(_, reason)| reason)
+            .collect()
+    }
+
+    #[test]
+    fn shared_and_placeholder_serials_are_virtual() {
+        let devices = vec![
+            usb_device("Camera A", "vid_1234&pid_0001", "SN0001"),
+            usb_device("Camera B", "vid_1234&pid_0002", "SN0001"),
+            usb_device("Camera C", "vid_1234&pid_0003", "0000"),
+            usb_device("Camera D", "vid_1234&pid_0004", "SN0004"),
+        ];
+
+        assert_eq!(
+            reasons(devices),
+            [
+                DetectionReason::DuplicateSerial("SN0001".to_string()),
+                DetectionReason::DuplicateSerial("SN0001".to_string()),
+                DetectionReason::PlaceholderSerial("0000".to_string()),
+                DetectionReason::Real,
+            ]
+        );
+    }
+
+    #[test]
+    fn a_serial_is_not_shared_with_the_same_device() {
+        // The same camera listed by two backends.
+        let mut directshow = usb_device("Camera A", "vid_1234&pid_0001", "SN0001");
+        directshow.enumeration_source = EnumerationSource::DirectShow;
+        let devices = vec![
+            usb_device("Camera A", "vid_1234&pid_0001", "SN0001"),
+            directshow,
+        ];
+
+        assert_eq!(
+            reasons(devices),
+            [DetectionReason::Real, DetectionReason::Real]
+        );
+    }
+
+    fn ids(vid: Option<&str>, pid: Option<&str>) -> (Option<String>, Option<String>) {
+        (vid.map(String::from), pid.map(String::from))
+    }
+
+    #[test]
+    fn partial_vid_pid_keeps_the_valid_half() {
+        assert_eq!(
+            parse_vid_pid(Some("\\\\?\\usb#vid_046d&pid_08#5&1a2b#{guid}")),
+            ids(Some("046D"), None)
+        );
+        assert_eq!(
+            parse_vid_pid(Some("\\\\?\\usb#vid_zz&pid_0825#5&1a2b#{guid}")),
+            ids(None, Some("0825"))
+        );
+    }
+
+    #[test]
+    fn partial_vid_pid_matches_only_wildcard_rules() {
+        let config = DetectionConfig::default()
+            .add_vid_pid("1234", "*")
+            .add_vid_pid("*", "ABCD")
+            .add_vid_pid("5678", "0001");
+        let reason = |path| detection_reason(&usb_device("Camera", path, "SN1"), &config);
+
+        assert_eq!(
+            reason("vid_1234&pid_zz"),
+            DetectionReason::VidPidMatch {
+                vid: "1234".to_string(),
+                pid: "*".to_string(),
             }
+        );
+        assert_eq!(
+            reason("vid_zz&pid_abcd"),
+            DetectionReason::VidPidMatch {
+                vid: "*".to_string(),
+                pid: "ABCD".to_string(),
+            }
+        );
+        assert_eq!(reason("vid_5678&pid_zz"), DetectionReason::Real);
+        assert_eq!(reason("vid_zz&pid_0001"), DetectionReason::Real);
+    }
+
+    #[test]
+    fn missing_pid_keeps_the_known_vendor_check() {
+        let config = DetectionConfig::default().add_vid_pid("046D", "0825");
+        let device = usb_device("Logitech OBS Capture", "vid_046d&pid_zz", "SN1");
+
+        assert_eq!(detection_reason(&device, &config), DetectionReason::Real);
+    }
+
+    #[test]
+    fn device_path_constructors_leave_unknown_fields_empty() {
+        let device = CameraDevice::from_device_path(
+            "\\\\?\\usb#vid_046d&pid_0825&mi_00#6&1a2b&0&0000#{guid}\\global",
+        );
+
+        assert_eq!(device.name, "Unknown");
+        assert_eq!(
+            (device.vid.as_deref(), device.pid.as_deref()),
+            (Some("046D"), Some("0825"))
+        );
+        assert_eq!(device.orientation, None);
+        assert_eq!(device.mirrored, None);
+        assert_eq!(device.built_in, None);
+        assert_eq!(device.manufacturer, None);
+        assert_eq!(device.driver, None);
+        assert_eq!(device.serial_number, None);
+        assert_eq!(
+            device.enumeration_source,
+            EnumerationSource::MediaFoundation
+        );
+    }
+
+    #[test]
+    fn blank_paths_are_left_unset() {
+        for path in ["", "  "] {
+            let first = CameraDevice::from_name_and_path("OBS Virtual Camera", path);
+            let second = CameraDevice::from_name_and_path("Snap Camera", path);
+
+            assert_eq!(first.device_path, None);
+            assert_eq!(first.serial_number, None);
+            assert_ne!(first, second);
+        }
+    }
+
+    #[test]
+    fn classify_stream_pulls_devices_on_demand() {
+        let config = DetectionConfig::default();
+        let produced = std::cell::Cell::new(0);
+        let devices = (0..).map(|index| {
+            produced.set(produced.get() + 1);
+            let name = if index % 3 == 0 {
+                "OBS Virtual Camera"
+            } else {
+                "Integrated Camera"
+            };
+            CameraDevice::from_name_and_path(name, "")
+        });
+
+        let mut stream = classify_stream(devices, &config);
+        let first = stream
+            .by_ref()
+            .take(3)
+            .map(|(device, is_virtual)| (device.name, is_virtual))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            first,
+            [
+                ("OBS Virtual Camera".to_string(), true),
+                ("Integrated Camera".to_string(), false),
+                ("Integrated Camera".to_string(), false),
+            ]
+        );
+        assert_eq!(produced.get(), 3);
+
+        // Nothing is collected, however long the stream runs.
+        let virtual_count = stream
+            .take(30_000)
+            .filter(|(_, is_virtual)| *is_virtual)
+            .count();
+        assert_eq!(virtual_count, 10_000);
+        assert_eq!(produced.get(), 30_003);
+    }
+
+    #[test]
+    fn continuity_cameras_are_phone_tethers() {
+        let by_name = macos_device("Jane's iPhone Camera", "Apple Inc.");
+        let by_model = CameraDevice {
+            driver: Some("iPad14,1".to_string()),
+            ..macos_device("Desk View Camera", "Apple Inc.")
         };
 
-        let mut class_enum: Option<IEnumMoniker> = None;
-        if enumerator
-            .CreateClassEnumerator(&CLSID_VideoInputDeviceCategory, &mut class_enum, 0)
-            .is_err()
-        {
-            CoUninitialize();
-            return devices;
+        assert_eq!(classify(&by_name), DeviceKind::PhoneTether);
+        assert_eq!(classify(&by_model), DeviceKind::PhoneTether);
+        assert_eq!(
+            classify(&macos_device("FaceTime HD Camera", "Apple Inc.")),
+            DeviceKind::Real
+        );
+    }
+
+    #[test]
+    fn phone_tethers_are_only_recognized_on_macos() {
+        let device = CameraDevice {
+            enumeration_source: EnumerationSource::DirectShow,
+            ..macos_device("Jane's iPhone Camera", "Apple Inc.")
+        };
+
+        assert_eq!(classify(&device), DeviceKind::Real);
+    }
+
+    #[test]
+    fn the_video_input_category_clsid_alone_is_not_virtual() {
+        let device = CameraDevice {
+            clsid: Some("{860BB310-5D01-11D0-BD3B-00A0C911CE86}".to_string()),
+            ..usb_device("USB Camera", "vid_1234&pid_5678", "SN1")
+        };
+
+        assert_eq!(classify_reason(&device), None);
+    }
+
+    #[test]
+    fn virtual_filter_clsids_are_virtual() {
+        for clsid in [
+            "{a3fce0f5-3493-419f-958a-aba1250ec20b}",
+            "{5c2cd55c-92ad-4999-8666-912bd3e70010}",
+        ] {
+            let device = CameraDevice {
+                clsid: Some(clsid.to_uppercase()),
+                enumeration_source: EnumerationSource::DirectShow,
+                ..CameraDevice::from_name_and_path("Camera", "")
+            };
+
+            assert_eq!(
+                classify_reason(&device),
+                Some(DetectionReason::ClsidMatch(clsid.to_string()))
+            );
         }
+    }
 
-        let mut class_enum = match class_enum {
-            Some(class_enum) => class_enum,
-            None => {
-                CoUninitialize();
-                return devices;
-            }
+    #[test]
+    fn backend_duplicates_collapse_in_a_hash_set() {
+        let path = format!(
+            "\\\\?\\usb#vid_046d&pid_085b&mi_00#7&1a2b&0&0000#{KSCATEGORY_VIDEO_CAMERA}\\global"
+        );
+        let media_foundation = CameraDevice::from_name_and_path("Logitech Webcam C925e", &path);
+        let directshow = CameraDevice {
+            name: "C925e".to_string(),
+            device_path: Some(path.to_uppercase().replace("\\\\?\\", "\\??\\")),
+            enumeration_source: EnumerationSource::DirectShow,
+            ..media_foundation.clone()
         };
+        let other = usb_device("Logitech Webcam C925e", "vid_046d&pid_085b", "SN2");
 
-        loop {
-            let mut monikers: [Option<IMoniker>; 1] = [None];
-            let mut fetched = 0;
-            if class_enum.Next(&mut monikers, &mut fetched).is_err() || fetched == 0 {
-                break;
-            }
+        let devices = [media_foundation.clone(), directshow, other]
+            .into_iter()
+            .collect::<std::collections::HashSet<_>>();
 
-            let Some(moniker) = monikers[0].take() else { continue };
+        assert_eq!(devices.len(), 2);
+        assert!(devices.contains(&media_foundation));
+    }
 
-            let mut property_bag = None;
-            if moniker.BindToStorage(None, None, &mut property_bag).is_err() {
-                continue;
-            }
+    #[test]
+    fn manufacturer_keyword_alone_is_below_the_threshold() {
+        let device = macos_device("Camera Extension", "obs-project");
 
-            let Some(property_bag) = property_bag else { continue };
-
-            let name = read_property_bag_string(&property_bag, "FriendlyName")
-                .unwrap_or_else(|| "Unknown Camera".to_string());
-            let manufacturer = read_property_bag_string(&property_bag, "Manufacturer");
-            let device_path = read_property_bag_string(&property_bag, "DevicePath");
-            let driver = read_property_bag_string(&property_bag, "Driver");
-            let clsid = read_property_bag_string(&property_bag, "CLSID");
-            let (vid, pid) = parse_vid_pid(device_path.as_deref());
-
-            devices.push(CameraDevice {
-                name,
-                manufacturer,
-                device_path,
-                driver,
-                vid,
-                pid,
-                clsid,
-            });
+        assert_eq!(score_device(&device, &DetectionConfig::default()), 0.6);
+        assert!(!is_virtual_camera(&device));
+
+        let extension = CameraDevice {
+            driver: Some("OBS Camera Extension Model".to_string()),
+            ..device
+        };
+        assert!(is_virtual_camera(&extension));
+        assert_eq!(
+            classify_reason(&extension),
+            Some(DetectionReason::NameKeyword("obs".to_string()))
+        );
+    }
+
+    #[test]
+    fn path_keywords_reach_the_threshold() {
+        let device = CameraDevice {
+            enumeration_source: EnumerationSource::DirectShow,
+            ..CameraDevice::from_name_and_path(
+                "Video Source",
+                &format!("\\\\?\\root#manycam#0000#{KSCATEGORY_VIDEO_CAMERA}"),
+            )
+        };
+
+        assert_eq!(score_device(&device, &DetectionConfig::default()), 0.7);
+        assert!(is_virtual_camera(&device));
+        assert_eq!(
+            classify_reason(&device),
+            Some(DetectionReason::NameKeyword("manycam".to_string()))
+        );
+    }
+
+    #[test]
+    fn whitelisted_devices_are_real_despite_blacklist_matches() {
+        let optics = macos_device("Virtual Reality Optics VR-CAM", "VR Optics");
+        let obs_ids = usb_device("Capture", "vid_0bda&pid_58f4", "SN0001");
+        let devices = vec![optics.clone(), obs_ids.clone()];
+        assert!(is_virtual_camera(&optics));
+        assert!(is_virtual_camera(&obs_ids));
+
+        let config = DetectionConfig {
+            whitelist: Whitelist::default()
+                .add_name("virtual reality optics")
+                .add_vid_pid("0BDA", "58F4"),
+            ..DetectionConfig::default()
+        };
+
+        assert_eq!(
+            classify_reasons(devices, &config)
+                .into_iter()
+                .map(|(_, reason)| reason)
+                .collect::<Vec<_>>(),
+            [DetectionReason::Real, DetectionReason::Real]
+        );
+    }
+
+    #[test]
+    fn a_whitelisted_pair_needs_both_ids() {
+        let mut device = macos_device("OBS Virtual Camera", "OBS");
+        device.vid = Some("0bda".to_string());
+        let config = DetectionConfig {
+            whitelist: Whitelist::default().add_vid_pid("0bda", "58f4"),
+            ..DetectionConfig::default()
+        };
+
+        assert!(!is_allowlisted(&device, &config));
+
+        let config = DetectionConfig {
+            whitelist: Whitelist::default().add_vid_pid("0bda", "*"),
+            ..config
+        };
+        assert!(!is_allowlisted(&device, &config));
+
+        device.pid = Some("0000".to_string());
+        assert!(is_allowlisted(&device, &config));
+    }
+
+    #[test]
+    fn short_keywords_match_whole_tokens_only() {
+        assert!(!is_virtual_camera(&macos_device("Kobson Webcam", "Kobson")));
+        assert!(is_virtual_camera(&macos_device("OBS Camera", "Unknown")));
+        assert!(is_virtual_camera(&macos_device("My obs-cam", "Unknown")));
+
+        assert!(!keyword_matches("kobson webcam", "obs", false));
+        assert!(keyword_matches("snap camera pro", "snap camera", false));
+        // Longer keywords still match inside a token, unless the field is a path.
+        assert!(keyword_matches("streamlabsdesktop", "streamlabs", false));
+        assert!(!keyword_matches("streamlabsdesktop", "streamlabs", true));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn detection_results_serialize_as_snake_case() {
+        let results = [
+            (DetectionResult::RealCamera, r#""real_camera""#),
+            (DetectionResult::VirtualCamera, r#""virtual_camera""#),
+            (DetectionResult::NoCamera, r#""no_camera""#),
+            (DetectionResult::NetworkCamera, r#""network_camera""#),
+            (
+                DetectionResult::Mixed {
+                    real_count: 1,
+                    virtual_count: 2,
+                },
+                r#"{"mixed":{"real_count":1,"virtual_count":2}}"#,
+            ),
+        ];
+
+        for (result, json) in results {
+            assert_eq!(serde_json::to_string(&result).unwrap(), json);
+            assert_eq!(
+                serde_json::from_str::<DetectionResult>(json).unwrap(),
+                result
+            );
         }
+    }
 
-        CoUninitialize();
+    #[test]
+    fn keywords_do_not_straddle_fields() {
+        // Joined without a separator the fields would read "live streamlabs inc".
+        assert!(!is_virtual_camera(&macos_device("Live Stream", "Labs Inc")));
+        assert_eq!(
+            classify_reason(&macos_device("OBS Virtual Camera", "OBS Project")),
+            Some(DetectionReason::NameKeyword("virtual".to_string()))
+        );
     }
 
-    devices
-}
+    #[cfg(feature = "serde")]
+    #[test]
+    fn devices_round_trip_through_json() {
+        let mut detailed = usb_device("Logitech C920", "vid_046d&pid_082d", "A1B2C3");
+        detailed.manufacturer = Some("Logitech".to_string());
+        detailed.driver = Some("usbvideo".to_string());
+        detailed.clsid = Some("{17cca71b-ecd7-11d0-b908-00a0c9223196}".to_string());
+        detailed.orientation = Some(90);
+        detailed.mirrored = Some(true);
+        detailed.built_in = Some(false);
+        detailed.hardware_ids = vec!["USB\\VID_046D&PID_082D".to_string()];
+        detailed.instance_id = Some("USB\\VID_046D&PID_082D\\A1B2C3".to_string());
+        let devices = vec![detailed, macos_device("FaceTime HD Camera", "Apple Inc.")];
 
-#[cfg(windows)]
-fn get_activate_string(
-    activate: &windows::Win32::Media::MediaFoundation::IMFActivate,
-    key: &windows::core::GUID,
-) -> Option<String> {
-    use windows::core::PWSTR;
+        let json = serde_json::to_string(&devices).unwrap();
+        let decoded: Vec<CameraDevice> = serde_json::from_str(&json).unwrap();
 
-    unsafe {
-        let mut string_ptr = PWSTR::null();
-        let mut length = 0;
-        if activate
-            .GetAllocatedString(key, &mut string_ptr, &mut length)
-            .is_err()
-        {
-            return None;
+        // `PartialEq` compares identity only, so compare every field.
+        assert_eq!(format!("{decoded:?}"), format!("{devices:?}"));
+
+        let value = serde_json::to_value(&devices[1]).unwrap();
+        assert_eq!(value["device_path"], serde_json::Value::Null);
+        assert_eq!(value["vid"], serde_json::Value::Null);
+        assert_eq!(value["enumeration_source"], "av_foundation");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn detection_reasons_round_trip_through_json() {
+        let reasons = [
+            DetectionReason::Real,
+            DetectionReason::NameKeyword("obs".to_string()),
+            DetectionReason::VidPidMatch {
+                vid: "0bda".to_string(),
+                pid: "58f4".to_string(),
+            },
+            DetectionReason::NoHardwarePath,
+        ];
+
+        let json = serde_json::to_string(&reasons).unwrap();
+
+        assert_eq!(
+            serde_json::from_str::<Vec<DetectionReason>>(&json).unwrap(),
+            reasons
+        );
+    }
+
+    #[test]
+    fn a_uvc_path_with_the_video_input_category_is_real() {
+        let category = "{860bb310-5d01-11d0-bd3b-00a0c911ce86}";
+        let device = CameraDevice {
+            enumeration_source: EnumerationSource::DirectShow,
+            ..CameraDevice::from_name_and_path(
+                "USB2.0 HD UVC WebCam",
+                &format!("\\\\?\\usb#vid_1234&pid_5678&mi_00#6&2f3a&0&0000#{category}\\global"),
+            )
+        };
+
+        assert!(!DetectionConfig::default()
+            .clsids
+            .iter()
+            .any(|clsid| clsid.eq_ignore_ascii_case(category)));
+        assert_eq!(classify_reason(&device), None);
+    }
+
+    #[test]
+    fn vid_pid_segments_end_at_the_next_delimiter() {
+        let cases = [
+            (
+                "\\\\?\\usb#vid_046d&pid_0825&mi_00#6&2f3a&0&0000#{guid}",
+                ids(Some("046D"), Some("0825")),
+            ),
+            (
+                "usb\\vid_1d6b&pid_01040002&rev_0100",
+                ids(Some("1D6B"), Some("01040002")),
+            ),
+            ("usb#vid_046d&pid_0825", ids(Some("046D"), Some("0825"))),
+            (
+                "\\\\?\\usb#vid_0c45&pid_6366\\global",
+                ids(Some("0C45"), Some("6366")),
+            ),
+        ];
+
+        for (path, expected) in cases {
+            assert_eq!(parse_vid_pid(Some(path)), expected, "{path}");
         }
-        if string_ptr.is_null() {
-            return None;
+    }
+
+    #[test]
+    fn non_hex_vid_pid_segments_are_rejected() {
+        let cases = [
+            "usb#vid_046d&pid_08g5#5&1a2b",
+            "usb#vid_04x6&pid_0825#5&1a2b",
+            "usb#vid_046d&pid_\\global",
+            "usb#vid_ 46d&pid_0825",
+        ];
+
+        assert_eq!(
+            cases.map(|path| parse_vid_pid(Some(path))),
+            [
+                ids(Some("046D"), None),
+                ids(None, Some("0825")),
+                ids(Some("046D"), None),
+                ids(None, Some("0825")),
+            ]
+        );
+    }
+
+    #[test]
+    fn the_pid_is_the_first_one_after_the_vid() {
+        let cases = [
+            (
+                "usb#pid_ffff#vid_046d&pid_0825&mi_00#vid_1111&pid_2222",
+                ids(Some("046D"), Some("0825")),
+            ),
+            (
+                "usb#vid_046d&mi_00#vid_0bda&pid_58f4",
+                ids(Some("046D"), Some("58F4")),
+            ),
+            ("root#image#0000#{guid}", ids(None, None)),
+            ("usb#vid_046d&pid_08", ids(Some("046D"), None)),
+            ("usb#vid_04", ids(None, None)),
+            ("usb#vid_", ids(None, None)),
+            ("", ids(None, None)),
+        ];
+
+        for (path, expected) in cases {
+            assert_eq!(parse_vid_pid(Some(path)), expected, "{path:?}");
         }
-        let slice = std::slice::from_raw_parts(string_ptr.0, length as usize);
-        let string = String::from_utf16_lossy(slice);
-        windows::Win32::System::Com::CoTaskMemFree(Some(string_ptr.0 as _));
-        Some(string)
-    }
-}
-
-#[cfg(windows)]
-fn read_property_bag_string(
-    property_bag: &windows::Win32::Media::DirectShow::IPropertyBag,
-    name: &str,
-) -> Option<String> {
-    use windows::core::BSTR;
-    use windows::Win32::System::Com::VARIANT;
-
-    unsafe {
-        let mut variant = VARIANT::default();
-        if property_bag
-            .Read(&BSTR::from(name), &mut variant, None)
-            .is_err()
-        {
-            return None;
+        assert_eq!(parse_vid_pid(None), ids(None, None));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn device_json_has_a_stable_schema() {
+        let value = serde_json::to_value(macos_device("FaceTime HD Camera", "Apple Inc.")).unwrap();
+        let mut keys = value
+            .as_object()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        keys.sort();
+
+        assert_eq!(
+            keys,
+            [
+                "built_in",
+                "clsid",
+                "device_path",
+                "driver",
+                "enumeration_source",
+                "hardware_ids",
+                "instance_id",
+                "manufacturer",
+                "mirrored",
+                "name",
+                "orientation",
+                "pid",
+                "serial_number",
+                "vid",
+            ]
+        );
+
+        let classification =
+            Classification::Virtual(DetectionReason::ClsidMatch("{a3fce0f5}".to_string()));
+        let json = serde_json::to_string(&classification).unwrap();
+        assert_eq!(json, r#"{"virtual":{"clsid_match":"{a3fce0f5}"}}"#);
+        assert_eq!(
+            serde_json::from_str::<Classification>(&json).unwrap(),
+            classification
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn enumerated_devices_export_as_a_json_array() {
+        if let Ok(json) = enumerate_devices_json() {
+            let devices: Vec<CameraDevice> = serde_json::from_str(&json).unwrap();
+            assert_eq!(serde_json::to_string(&devices).unwrap(), json);
         }
+    }
 
-        if variant.Anonymous.Anonymous.vt as u32
-            != windows::Win32::System::Variant::VT_BSTR.0
-        {
-            return None;
+    #[test]
+    fn real_and_virtual_cameras_together_are_mixed() {
+        let real = usb_device("Logitech C920", "vid_046d&pid_082d", "A1B2C3");
+        let virtual_camera = macos_device("OBS Virtual Camera", "OBS Project");
+
+        assert_eq!(
+            detect_cameras_from(&[real.clone(), virtual_camera.clone()]),
+            DetectionResult::Mixed {
+                real_count: 1,
+                virtual_count: 1,
+            }
+        );
+        assert_eq!(detect_cameras_from(&[real]), DetectionResult::RealCamera);
+        assert_eq!(
+            detect_cameras_from(&[virtual_camera]),
+            DetectionResult::VirtualCamera
+        );
+        assert_eq!(detect_cameras_from(&[]), DetectionResult::NoCamera);
+    }
+
+    #[test]
+    fn summary_counts_each_kind() {
+        let devices = vec![
+            usb_device("Logitech C920", "vid_046d&pid_082d", "A1B2C3"),
+            usb_device("Microsoft LifeCam", "vid_045e&pid_0810", "D4E5F6"),
+            macos_device("OBS Virtual Camera", "OBS Project"),
+            macos_device("Lobby", "ONVIF IP Camera"),
+        ];
+
+        let summary = summarize(devices.clone(), &DetectionConfig::default());
+        assert_eq!(
+            (
+                summary.real_count(),
+                summary.virtual_count(),
+                summary.network_count(),
+                summary.total
+            ),
+            (2, 1, 1, 4)
+        );
+
+        let config = DetectionConfig::default().with_network_cameras_as_real(true);
+        let summary = summarize(devices, &config);
+        assert_eq!((summary.real_count(), summary.network_count()), (3, 0));
+    }
+
+    #[test]
+    fn display_strings() {
+        assert_eq!(
+            DetectionResult::RealCamera.to_string(),
+            "Real camera detected"
+        );
+        assert_eq!(
+            DetectionResult::VirtualCamera.to_string(),
+            "Virtual camera detected"
+        );
+        assert_eq!(DetectionResult::NoCamera.to_string(), "No camera found");
+        assert_eq!(
+            DetectionResult::Mixed {
+                real_count: 2,
+                virtual_count: 1,
+            }
+            .to_string(),
+            "Real and virtual cameras detected (2 real, 1 virtual)"
+        );
+
+        let usb = usb_device("Logitech C920", "vid_046d&pid_082d", "A1B2C3");
+        assert_eq!(usb.to_string(), "Logitech C920 (VID:046D PID:082D)");
+        let facetime = macos_device("FaceTime HD Camera", "Apple Inc.");
+        assert_eq!(facetime.to_string(), "FaceTime HD Camera (VID:? PID:?)");
+
+        let verbose = facetime.display_verbose();
+        let lines = verbose.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "Name: FaceTime HD Camera");
+        for line in [
+            "Manufacturer: Apple Inc.",
+            "Driver: ?",
+            "Device path: ?",
+            "CLSID: ?",
+            "Source: AvFoundation",
+        ] {
+            assert!(lines.contains(&line), "{line:?} missing from {verbose}");
         }
+    }
 
-        let bstr = variant.Anonymous.Anonymous.Anonymous.bstrVal;
-        if bstr.is_null() {
-            return None;
+    #[test]
+    fn hardware_paths_by_enumerator() {
+        let cases = [
+            ("\\\\?\\usb#vid_046d&pid_082d#A1B2C3#{guid}\\global", true),
+            ("\\\\?\\pci#ven_8086&dev_9a19#3&11583659&0&28#{guid}", true),
+            ("\\\\?\\acpi#int3474#0#{guid}", true),
+            ("\\??\\ACPI#OVTI01A0#1#{guid}", true),
+            ("ACPI\\INT33BE\\0", true),
+            ("\\\\?\\root#image#0000#{guid}", false),
+            ("\\\\?\\swd#mmdevapi#{0.0.1.00000000}", false),
+            ("\\\\?\\sw#{guid}#obs-virtualcam", false),
+        ];
+        for (path, expected) in cases {
+            let device = CameraDevice::from_name_and_path("Camera", path);
+            assert_eq!(has_hardware_path(&device), expected, "{path}");
         }
-        Some(BSTR::from_raw(bstr).to_string())
+
+        let filter = CameraDevice {
+            enumeration_source: EnumerationSource::DirectShow,
+            ..CameraDevice::from_name_and_path("Camera", "")
+        };
+        assert!(!has_hardware_path(&filter));
+        assert!(has_hardware_path(&macos_device(
+            "FaceTime HD Camera",
+            "Apple Inc."
+        )));
+
+        let strict = DetectionConfig::default().strict_hardware_check(true);
+        assert_eq!(
+            classify_reasons(vec![filter], &strict)
+                .into_iter()
+                .map(|(_, reason)| reason)
+                .collect::<Vec<_>>(),
+            [DetectionReason::NoHardwarePath]
+        );
     }
-}
 
-fn parse_vid_pid(device_path: Option<&str>) -> (Option<String>, Option<String>) {
-    let Some(device_path) = device_path else {
-        return (None, None);
-    };
+    #[test]
+    fn serial_number_is_the_segment_after_the_ids() {
+        let cases = [
+            (
+                r"\\?\usb#vid_046d&pid_0825#LZ12345#{e5323777-f976-4f5b-9b55-b94699c46e44}\global",
+                Some("LZ12345"),
+            ),
+            (r"\\?\USB#VID_046D&PID_0825#LZ12345", Some("LZ12345")),
+            // Composite devices and ports without a serial get a generated
+            // instance ID containing `&`.
+            (
+                r"\\?\usb#vid_046d&pid_0825&mi_00#6&2f3a&0&0000#{guid}",
+                None,
+            ),
+            (r"\\?\usb#vid_046d&pid_0825#{guid}\global", None),
+            (r"\\?\usb#vid_046d&pid_0825##{guid}", None),
+            (r"\\?\usb#vid_046d&pid_0825", None),
+            (r"\\?\root#image#0000#{guid}", None),
+        ];
 
-    let device_path_lower = device_path.to_lowercase();
-    let vid = extract_segment(&device_path_lower, "vid_");
-    let pid = extract_segment(&device_path_lower, "pid_");
-    (vid, pid)
-}
+        for (path, expected) in cases {
+            assert_eq!(
+                parse_serial_number(Some(path)).as_deref(),
+                expected,
+                "{path}"
+            );
+        }
+        assert_eq!(parse_serial_number(None), None);
+    }
 
-fn extract_segment(source: &str, token: &str) -> Option<String> {
-    let start = source.find(token)? + token.len();
-    let segment = source[start..].chars().take(4).collect::<String>();
-    if segment.len() == 4 {
-        Some(segment)
-    } else {
-        None
+    #[test]
+    fn known_real_vendors_on_usb_override_keywords() {
+        let mut kiyo = usb_device("Razer Kiyo", "vid_1532&pid_0e03", "KY0001");
+        kiyo.driver = Some("Razer Virtual Audio".to_string());
+        let hp = usb_device(
+            "HP Wide Vision Virtual Camera",
+            "vid_04f2&pid_b6dd",
+            "HP0001",
+        );
+        assert_eq!(classify_reason(&kiyo), None);
+        assert_eq!(classify_reason(&hp), None);
+
+        // A software device copying a real VID has no USB path.
+        let copied = CameraDevice::from_name_and_path(
+            "HP Wide Vision Virtual Camera",
+            "\\\\?\\root#vid_04f2&pid_b6dd#0000#{guid}",
+        );
+        assert!(is_virtual_camera(&copied));
+
+        // A VID/PID rule names the exact device and beats the vendor.
+        let obs = usb_device("Capture", "vid_0bda&pid_58f4", "SN0001");
+        assert!(is_virtual_camera(&obs));
+    }
+
+    #[test]
+    fn builder_extends_the_known_real_vendors() {
+        let device = usb_device("Acme Virtual Inspection Cam", "vid_2222&pid_0001", "AC0001");
+        assert!(is_virtual_camera(&device));
+
+        let detector = crate::camera::detector::Detector::builder()
+            .known_real_vid("2222")
+            .build();
+        assert_eq!(detector.classify(&device), Classification::Real);
+    }
+
+    #[test]
+    fn paths_to_the_same_device_normalize_alike() {
+        let canonical = r"\\?\usb#vid_046d&pid_0825#lz12345#{e5323777-f976-4f5b-9b55-b94699c46e44}";
+        let aliases = [
+            r"\\?\USB#VID_046D&PID_0825#LZ12345#{E5323777-F976-4F5B-9B55-B94699C46E44}",
+            r"\\?\usb#vid_046d&pid_0825&mi_00#lz12345#{e5323777-f976-4f5b-9b55-b94699c46e44}",
+            r"\\?\usb#vid_046d&pid_0825#lz12345#{e5323777-f976-4f5b-9b55-b94699c46e44}#0",
+            r"\\\\?//usb#vid_046d&pid_0825#lz12345#{e5323777-f976-4f5b-9b55-b94699c46e44}",
+        ];
+
+        let normalized = normalize_device_path(canonical);
+        assert_eq!(
+            normalized,
+            r"\?\usb#vid_046d&pid_0825#lz12345#{e5323777-f976-4f5b-9b55-b94699c46e44}"
+        );
+        for alias in aliases {
+            assert_eq!(normalize_device_path(alias), normalized, "{alias}");
+        }
+
+        // Other interfaces of a composite device stay distinct.
+        assert_ne!(
+            normalize_device_path(r"\\?\usb#vid_046d&pid_0825&mi_02#lz12345#{guid}"),
+            normalize_device_path(r"\\?\usb#vid_046d&pid_0825&mi_00#lz12345#{guid}")
+        );
+    }
+
+    #[test]
+    fn macos_model_ids_flag_virtual_cameras() {
+        // The model IDs of the OBS DAL plugin and camera extension.
+        for model_id in ["obs-virtual-cam-model", "OBS Camera Extension Model"] {
+            let device = CameraDevice {
+                driver: Some(model_id.to_string()),
+                ..macos_device("Camera", "Unknown")
+            };
+
+            assert_eq!(
+                score_device(&device, &DetectionConfig::default()),
+                0.95,
+                "{model_id}"
+            );
+            assert!(is_virtual_camera(&device), "{model_id}");
+        }
+
+        let facetime = CameraDevice {
+            driver: Some("Apple Camera VendorID_0x106B ProductID_0x1570".to_string()),
+            ..macos_device("FaceTime HD Camera", "Apple Inc.")
+        };
+        assert!(!is_virtual_camera(&facetime));
     }
 }
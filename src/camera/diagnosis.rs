@@ -0,0 +1,626 @@
+#[cfg(windows)]
+use std::time::Duration;
+
+use super::device_enum::CameraDevice;
+#[cfg(windows)]
+use super::platform::windows::registry::{
+    enumerate_registry_subkeys, read_registry_string, read_registry_u32, read_registry_u64,
+};
+#[cfg(windows)]
+use super::platform::windows::{read_property_bag_string, ComApartment};
+
+const E_ACCESSDENIED: i32 = 0x8007_0005_u32 as i32;
+#[cfg(not(windows))]
+const E_NOTIMPL: i32 = 0x8000_4001_u32 as i32;
+const HRESULT_ERROR_BUSY: i32 = 0x8007_00AA_u32 as i32;
+const HRESULT_ERROR_SHARING_VIOLATION: i32 = 0x8007_0020_u32 as i32;
+#[cfg(windows)]
+const HRESULT_ERROR_NO_SYSTEM_RESOURCES: i32 = 0x8007_05AA_u32 as i32;
+#[cfg(windows)]
+const HRESULT_ERROR_TIMEOUT: i32 = 0x8007_05B4_u32 as i32;
+const MF_E_HW_MFT_FAILED_START_STREAMING: i32 = 0xC00D_3704_u32 as i32;
+const MF_E_VIDEO_RECORDING_DEVICE_INVALIDATED: i32 = 0xC00D_3EA2_u32 as i32;
+const MF_E_VIDEO_RECORDING_DEVICE_PREEMPTED: i32 = 0xC00D_3EA3_u32 as i32;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum OpenFailureDiagnosis {
+    ConsentDenied,
+    DeviceBusy {
+        app: Option<String>,
+    },
+    /// `code` is the devnode problem code (`CM_PROB_*`) when the device
+    /// reports one, otherwise the raw HRESULT bits of the failed open.
+    DriverFailure {
+        code: u32,
+    },
+    Unknown {
+        hresult: i32,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ConsentState {
+    Allowed,
+    Denied,
+    Unknown,
+}
+
+/// Whether the Windows privacy settings let desktop apps use the camera.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+#[non_exhaustive]
+pub enum CameraAccessStatus {
+    Allowed,
+    /// The user turned camera access off.
+    DeniedByUser,
+    /// Group policy or the device-wide switch turned camera access off.
+    DeniedByPolicy,
+    #[default]
+    Unknown,
+}
+
+/// Reads the camera privacy settings; `Unknown` on other platforms.
+pub fn camera_access_status() -> CameraAccessStatus {
+    #[cfg(windows)]
+    {
+        read_camera_access_status()
+    }
+
+    #[cfg(not(windows))]
+    {
+        CameraAccessStatus::Unknown
+    }
+}
+
+pub fn diagnose_hresult(
+    hresult: i32,
+    consent: ConsentState,
+    problem_code: Option<u32>,
+) -> OpenFailureDiagnosis {
+    if let Some(code) = problem_code.filter(|code| *code != 0) {
+        return OpenFailureDiagnosis::DriverFailure { code };
+    }
+
+    match hresult {
+        E_ACCESSDENIED if consent != ConsentState::Allowed => OpenFailureDiagnosis::ConsentDenied,
+        HRESULT_ERROR_BUSY
+        | HRESULT_ERROR_SHARING_VIOLATION
+        | MF_E_VIDEO_RECORDING_DEVICE_PREEMPTED => OpenFailureDiagnosis::DeviceBusy { app: None },
+        MF_E_HW_MFT_FAILED_START_STREAMING | MF_E_VIDEO_RECORDING_DEVICE_INVALIDATED => {
+            OpenFailureDiagnosis::DriverFailure {
+                code: hresult as u32,
+            }
+        }
+        _ => OpenFailureDiagnosis::Unknown { hresult },
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Availability {
+    Available,
+    InUseByOtherProcess,
+    AccessDenied,
+    Unknown,
+}
+
+/// Briefly opens `device` and reads one frame to see whether it can be used.
+///
+/// The camera is released before this returns, so its LED is only on for
+/// the probe. Devices without a symbolic link, such as DirectShow-only
+/// virtual cameras, are opened through DirectShow instead: their filter is
+/// connected to a null renderer and the graph is run. A device that does not
+/// start within five seconds gives `Unknown`, as do other platforms.
+pub fn check_availability(device: &CameraDevice) -> Availability {
+    #[cfg(windows)]
+    {
+        let result = match device.device_path.as_deref() {
+            Some(device_path) => start_streaming(device_path),
+            None => {
+                let name = device.name.clone();
+                with_stream_timeout(move || run_directshow_graph(&name))
+            }
+        };
+        match result {
+            Ok(()) => Availability::Available,
+            Err(hresult) => availability_from_hresult(hresult),
+        }
+    }
+
+    #[cfg(not(windows))]
+    {
+        let _ = device;
+        Availability::Unknown
+    }
+}
+
+// A camera held by another app typically fails to start streaming with
+// MF_E_HW_MFT_FAILED_START_STREAMING rather than a sharing error; under
+// DirectShow, running the graph fails with ERROR_NO_SYSTEM_RESOURCES.
+#[cfg(windows)]
+fn availability_from_hresult(hresult: i32) -> Availability {
+    match hresult {
+        E_ACCESSDENIED => Availability::AccessDenied,
+        HRESULT_ERROR_BUSY
+        | HRESULT_ERROR_SHARING_VIOLATION
+        | HRESULT_ERROR_NO_SYSTEM_RESOURCES
+        | MF_E_VIDEO_RECORDING_DEVICE_PREEMPTED
+        | MF_E_HW_MFT_FAILED_START_STREAMING => Availability::InUseByOtherProcess,
+        _ => Availability::Unknown,
+    }
+}
+
+/// Starts streaming from `device` and explains why that fails; `None` when
+/// a frame arrives. Waiting more than five seconds for the first frame gives
+/// `Unknown` with `HRESULT_FROM_WIN32(ERROR_TIMEOUT)`.
+pub fn diagnose_open_failure(device: &CameraDevice) -> Option<OpenFailureDiagnosis> {
+    #[cfg(windows)]
+    {
+        diagnose_windows_open_failure(device)
+    }
+
+    #[cfg(not(windows))]
+    {
+        let _ = device;
+        Some(OpenFailureDiagnosis::Unknown { hresult: E_NOTIMPL })
+    }
+}
+
+#[cfg(windows)]
+fn diagnose_windows_open_failure(device: &CameraDevice) -> Option<OpenFailureDiagnosis> {
+    const E_INVALIDARG: i32 = 0x8007_0057_u32 as i32;
+
+    let Some(device_path) = device.device_path.as_deref() else {
+        return Some(OpenFailureDiagnosis::Unknown {
+            hresult: E_INVALIDARG,
+        });
+    };
+
+    let hresult = match start_streaming(device_path) {
+        Ok(()) => return None,
+        Err(hresult) => hresult,
+    };
+
+    let consent = read_consent_state();
+    let problem_code = instance_id_from_symbolic_link(device_path)
+        .and_then(|instance_id| read_devnode_problem_code(&instance_id));
+
+    match diagnose_hresult(hresult, consent, problem_code) {
+        OpenFailureDiagnosis::DeviceBusy { .. } => Some(OpenFailureDiagnosis::DeviceBusy {
+            app: find_app_using_camera(),
+        }),
+        OpenFailureDiagnosis::DriverFailure { code } if problem_code.unwrap_or(0) == 0 => {
+            match find_app_using_camera() {
+                Some(app) => Some(OpenFailureDiagnosis::DeviceBusy { app: Some(app) }),
+                None => Some(OpenFailureDiagnosis::DriverFailure { code }),
+            }
+        }
+        diagnosis => Some(diagnosis),
+    }
+}
+
+// How long to wait for the device to start. A wedged driver can block
+// ReadSample or a graph's Run indefinitely, so the probe runs on its own
+// thread; that thread stays blocked until the driver returns and then
+// cleans up after itself.
+#[cfg(windows)]
+const STREAM_START_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[cfg(windows)]
+fn with_stream_timeout(
+    probe: impl FnOnce() -> Result<(), i32> + Send + 'static,
+) -> Result<(), i32> {
+    use std::sync::mpsc;
+
+    const E_FAIL: i32 = 0x8000_4005_u32 as i32;
+
+    let (sender, receiver) = mpsc::channel();
+    std::thread::Builder::new()
+        .name("camera-diagnosis".to_string())
+        .spawn(move || {
+            // The receiver is gone when the wait timed out.
+            sender.send(probe()).ok();
+        })
+        .map_err(|_| E_FAIL)?;
+
+    receiver
+        .recv_timeout(STREAM_START_TIMEOUT)
+        .unwrap_or(Err(HRESULT_ERROR_TIMEOUT))
+}
+
+// Creating the source succeeds even while another app streams from the
+// camera; only starting a stream of our own fails then.
+#[cfg(windows)]
+fn start_streaming(symbolic_link: &str) -> Result<(), i32> {
+    let symbolic_link = symbolic_link.to_string();
+    with_stream_timeout(move || read_first_sample(&symbolic_link))
+}
+
+#[cfg(windows)]
+fn read_first_sample(symbolic_link: &str) -> Result<(), i32> {
+    use windows::core::HSTRING;
+    use windows::Win32::Media::MediaFoundation::{
+        MFCreateAttributes, MFCreateDeviceSource, MFCreateSourceReaderFromMediaSource, MFShutdown,
+        MFStartup, MF_DEVSOURCE_ATTRIBUTE_SOURCE_TYPE,
+        MF_DEVSOURCE_ATTRIBUTE_SOURCE_TYPE_VIDCAP_GUID,
+        MF_DEVSOURCE_ATTRIBUTE_SOURCE_TYPE_VIDCAP_SYMBOLIC_LINK,
+        MF_SOURCE_READER_FIRST_VIDEO_STREAM, MF_VERSION,
+    };
+
+    let _com = ComApartment::enter()?;
+
+    unsafe {
+        if let Err(error) = MFStartup(MF_VERSION, 0) {
+            return Err(error.code().0);
+        }
+
+        let result = (|| {
+            let mut attributes = None;
+            MFCreateAttributes(&mut attributes, 2)?;
+            let attributes = attributes.ok_or_else(windows::core::Error::empty)?;
+            attributes.SetGUID(
+                &MF_DEVSOURCE_ATTRIBUTE_SOURCE_TYPE,
+                &MF_DEVSOURCE_ATTRIBUTE_SOURCE_TYPE_VIDCAP_GUID,
+            )?;
+            attributes.SetString(
+                &MF_DEVSOURCE_ATTRIBUTE_SOURCE_TYPE_VIDCAP_SYMBOLIC_LINK,
+                &HSTRING::from(symbolic_link),
+            )?;
+            let source = MFCreateDeviceSource(&attributes)?;
+
+            let streamed = MFCreateSourceReaderFromMediaSource(&source, None).and_then(|reader| {
+                let mut flags = 0;
+                let mut sample = None;
+                reader.ReadSample(
+                    MF_SOURCE_READER_FIRST_VIDEO_STREAM.0 as u32,
+                    0,
+                    None,
+                    Some(&mut flags),
+                    None,
+                    Some(&mut sample),
+                )
+            });
+
+            source.Shutdown().ok();
+            streamed
+        })();
+
+        MFShutdown().ok();
+
+        result.map_err(|error| error.code().0)
+    }
+}
+
+// The Null Renderer filter from qedit.h, which the windows crate leaves out.
+#[cfg(windows)]
+const CLSID_NULL_RENDERER: windows::core::GUID =
+    windows::core::GUID::from_u128(0xc1f400a4_3f08_11d3_9f0b_006008039e37);
+
+#[cfg(windows)]
+fn run_directshow_graph(name: &str) -> Result<(), i32> {
+    use windows::core::{w, Interface};
+    use windows::Win32::Media::DirectShow::{
+        IBaseFilter, ICaptureGraphBuilder2, IGraphBuilder, IMediaControl,
+    };
+    use windows::Win32::Media::MediaFoundation::{
+        CLSID_CaptureGraphBuilder2, CLSID_FilterGraph, MEDIATYPE_Video, PIN_CATEGORY_CAPTURE,
+    };
+    use windows::Win32::System::Com::{CoCreateInstance, CLSCTX_INPROC_SERVER};
+
+    let _com = ComApartment::enter()?;
+
+    unsafe {
+        let result = (|| {
+            let capture = bind_directshow_filter(name)?;
+            let graph: IGraphBuilder =
+                CoCreateInstance(&CLSID_FilterGraph, None, CLSCTX_INPROC_SERVER)?;
+            let builder: ICaptureGraphBuilder2 =
+                CoCreateInstance(&CLSID_CaptureGraphBuilder2, None, CLSCTX_INPROC_SERVER)?;
+            builder.SetFiltergraph(&graph)?;
+            let renderer: IBaseFilter =
+                CoCreateInstance(&CLSID_NULL_RENDERER, None, CLSCTX_INPROC_SERVER)?;
+            graph.AddFilter(&capture, w!("Capture"))?;
+            graph.AddFilter(&renderer, w!("Null Renderer"))?;
+            builder.RenderStream(
+                Some(&PIN_CATEGORY_CAPTURE),
+                &MEDIATYPE_Video,
+                &capture,
+                None,
+                &renderer,
+            )?;
+
+            let control: IMediaControl = graph.cast()?;
+            let running = control.Run().and_then(|()| {
+                control
+                    .GetState(STREAM_START_TIMEOUT.as_millis() as i32)
+                    .map(drop)
+            });
+            control.Stop().ok();
+            running
+        })();
+
+        result.map_err(|error| error.code().0)
+    }
+}
+
+// Binds the first video input filter named `name`; DirectShow-only devices
+// have nothing else to tell them apart by.
+#[cfg(windows)]
+unsafe fn bind_directshow_filter(
+    name: &str,
+) -> windows::core::Result<windows::Win32::Media::DirectShow::IBaseFilter> {
+    use windows::Win32::Foundation::E_INVALIDARG;
+    use windows::Win32::Media::DirectShow::ICreateDevEnum;
+    use windows::Win32::Media::MediaFoundation::{
+        CLSID_SystemDeviceEnum, CLSID_VideoInputDeviceCategory,
+    };
+    use windows::Win32::System::Com::{
+        CoCreateInstance, IEnumMoniker, IMoniker, CLSCTX_INPROC_SERVER,
+    };
+
+    let enumerator: ICreateDevEnum =
+        CoCreateInstance(&CLSID_SystemDeviceEnum, None, CLSCTX_INPROC_SERVER)?;
+    let mut class_enum: Option<IEnumMoniker> = None;
+    enumerator.CreateClassEnumerator(&CLSID_VideoInputDeviceCategory, &mut class_enum, 0)?;
+    let class_enum = class_enum.ok_or_else(|| windows::core::Error::from(E_INVALIDARG))?;
+
+    loop {
+        let mut monikers: [Option<IMoniker>; 1] = [None];
+        let mut fetched = 0;
+        if class_enum.Next(&mut monikers, Some(&mut fetched)).is_err() || fetched == 0 {
+            return Err(E_INVALIDARG.into());
+        }
+        let Some(moniker) = monikers[0].take() else {
+            continue;
+        };
+        let friendly_name = moniker
+            .BindToStorage(None, None)
+            .ok()
+            .and_then(|property_bag| read_property_bag_string(&property_bag, "FriendlyName"));
+        if friendly_name.as_deref() == Some(name) {
+            return moniker.BindToObject(None, None);
+        }
+    }
+}
+
+#[cfg(windows)]
+const APP_PRIVACY_POLICY: &str = "SOFTWARE\\Policies\\Microsoft\\Windows\\AppPrivacy";
+
+#[cfg(windows)]
+const CONSENT_STORE_WEBCAM: &str =
+    "Software\\Microsoft\\Windows\\CurrentVersion\\CapabilityAccessManager\\ConsentStore\\webcam";
+
+#[cfg(windows)]
+fn read_consent_state() -> ConsentState {
+    use windows::Win32::System::Registry::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE};
+
+    let machine = read_registry_string(HKEY_LOCAL_MACHINE, CONSENT_STORE_WEBCAM, "Value");
+    let user = read_registry_string(HKEY_CURRENT_USER, CONSENT_STORE_WEBCAM, "Value");
+
+    match (machine.as_deref(), user.as_deref()) {
+        (Some("Deny"), _) | (_, Some("Deny")) => ConsentState::Denied,
+        (_, Some("Allow")) => ConsentState::Allowed,
+        (Some("Allow"), None) => ConsentState::Allowed,
+        _ => ConsentState::Unknown,
+    }
+}
+
+#[cfg(windows)]
+fn read_camera_access_status() -> CameraAccessStatus {
+    use windows::Win32::System::Registry::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE};
+
+    // 1 forces access on and 2 forces it off; 0 or no value leaves it to
+    // the settings below.
+    match read_registry_u32(
+        HKEY_LOCAL_MACHINE,
+        APP_PRIVACY_POLICY,
+        "LetAppsAccessCamera",
+    ) {
+        Some(1) => return CameraAccessStatus::Allowed,
+        Some(2) => return CameraAccessStatus::DeniedByPolicy,
+        _ => {}
+    }
+
+    let machine = read_registry_string(HKEY_LOCAL_MACHINE, CONSENT_STORE_WEBCAM, "Value");
+    let user = read_registry_string(HKEY_CURRENT_USER, CONSENT_STORE_WEBCAM, "Value");
+    // The separate switch for desktop (non-packaged) apps.
+    let desktop_apps = read_registry_string(
+        HKEY_CURRENT_USER,
+        &format!("{CONSENT_STORE_WEBCAM}\\NonPackaged"),
+        "Value",
+    );
+
+    match (machine.as_deref(), user.as_deref(), desktop_apps.as_deref()) {
+        (Some("Deny"), _, _) => CameraAccessStatus::DeniedByPolicy,
+        (_, Some("Deny"), _) | (_, _, Some("Deny")) => CameraAccessStatus::DeniedByUser,
+        (_, Some("Allow"), _) | (Some("Allow"), None, _) => CameraAccessStatus::Allowed,
+        _ => CameraAccessStatus::Unknown,
+    }
+}
+
+#[cfg(windows)]
+fn find_app_using_camera() -> Option<String> {
+    use windows::Win32::System::Registry::HKEY_CURRENT_USER;
+
+    let packaged = enumerate_registry_subkeys(HKEY_CURRENT_USER, CONSENT_STORE_WEBCAM)
+        .into_iter()
+        .filter(|name| name != "NonPackaged")
+        .map(|name| (format!("{CONSENT_STORE_WEBCAM}\\{name}"), name));
+    let non_packaged_root = format!("{CONSENT_STORE_WEBCAM}\\NonPackaged");
+    let non_packaged = enumerate_registry_subkeys(HKEY_CURRENT_USER, &non_packaged_root)
+        .into_iter()
+        .map(|name| {
+            (
+                format!("{non_packaged_root}\\{name}"),
+                name.replace('#', "\\"),
+            )
+        });
+
+    packaged.chain(non_packaged).find_map(|(key, app)| {
+        let start = read_registry_u64(HKEY_CURRENT_USER, &key, "LastUsedTimeStart")?;
+        let stop = read_registry_u64(HKEY_CURRENT_USER, &key, "LastUsedTimeStop")?;
+        (start != 0 && stop == 0).then_some(app)
+    })
+}
+
+#[cfg(windows)]
+pub(crate) fn instance_id_from_symbolic_link(symbolic_link: &str) -> Option<String> {
+    let trimmed = symbolic_link
+        .strip_prefix("\\\\?\\")
+        .or_else(|| symbolic_link.strip_prefix("\\??\\"))?;
+    let mut segments = trimmed.split('#').collect::<Vec<_>>();
+    if segments.last().is_some_and(|last| last.starts_with('{')) {
+        segments.pop();
+    }
+    if segments.len() < 2 {
+        return None;
+    }
+    Some(segments.join("\\"))
+}
+
+#[cfg(windows)]
+fn read_devnode_problem_code(instance_id: &str) -> Option<u32> {
+    use windows::core::HSTRING;
+    use windows::Win32::Devices::DeviceAndDriverInstallation::{
+        CM_Get_DevNode_Status, CM_Locate_DevNodeW, CM_DEVNODE_STATUS_FLAGS,
+        CM_LOCATE_DEVNODE_NORMAL, CM_PROB, CR_SUCCESS, DN_HAS_PROBLEM,
+    };
+
+    unsafe {
+        let mut devinst = 0;
+        if CM_Locate_DevNodeW(
+            &mut devinst,
+            &HSTRING::from(instance_id),
+            CM_LOCATE_DEVNODE_NORMAL,
+        ) != CR_SUCCESS
+        {
+            return None;
+        }
+
+        let mut status = CM_DEVNODE_STATUS_FLAGS::default();
+        let mut problem = CM_PROB::default();
+        if CM_Get_DevNode_Status(&mut status, &mut problem, devinst, 0) != CR_SUCCESS {
+            return None;
+        }
+
+        if status.0 & DN_HAS_PROBLEM.0 != 0 {
+            Some(problem.0)
+        } else {
+            Some(0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const E_FAIL: i32 = 0x8000_4005_u32 as i32;
+
+    #[test]
+    fn diagnose_hresult_without_problem_code() {
+        use ConsentState::{Allowed, Denied, Unknown};
+
+        let cases = [
+            (E_ACCESSDENIED, Denied, OpenFailureDiagnosis::ConsentDenied),
+            (E_ACCESSDENIED, Unknown, OpenFailureDiagnosis::ConsentDenied),
+            (
+                E_ACCESSDENIED,
+                Allowed,
+                OpenFailureDiagnosis::Unknown {
+                    hresult: E_ACCESSDENIED,
+                },
+            ),
+            (
+                HRESULT_ERROR_BUSY,
+                Allowed,
+                OpenFailureDiagnosis::DeviceBusy { app: None },
+            ),
+            (
+                HRESULT_ERROR_SHARING_VIOLATION,
+                Denied,
+                OpenFailureDiagnosis::DeviceBusy { app: None },
+            ),
+            (
+                MF_E_VIDEO_RECORDING_DEVICE_PREEMPTED,
+                Unknown,
+                OpenFailureDiagnosis::DeviceBusy { app: None },
+            ),
+            (
+                MF_E_HW_MFT_FAILED_START_STREAMING,
+                Allowed,
+                OpenFailureDiagnosis::DriverFailure {
+                    code: MF_E_HW_MFT_FAILED_START_STREAMING as u32,
+                },
+            ),
+            (
+                MF_E_VIDEO_RECORDING_DEVICE_INVALIDATED,
+                Denied,
+                OpenFailureDiagnosis::DriverFailure {
+                    code: MF_E_VIDEO_RECORDING_DEVICE_INVALIDATED as u32,
+                },
+            ),
+            (
+                E_FAIL,
+                Denied,
+                OpenFailureDiagnosis::Unknown { hresult: E_FAIL },
+            ),
+        ];
+
+        for (hresult, consent, expected) in cases {
+            assert_eq!(
+                diagnose_hresult(hresult, consent, None),
+                expected,
+                "{hresult:#010x} with {consent:?}"
+            );
+            // A devnode without a problem reports code 0.
+            assert_eq!(
+                diagnose_hresult(hresult, consent, Some(0)),
+                expected,
+                "{hresult:#010x} with {consent:?} and problem code 0"
+            );
+        }
+    }
+
+    #[test]
+    fn problem_code_wins_over_hresult() {
+        const CM_PROB_FAILED_START: u32 = 10;
+
+        for hresult in [E_ACCESSDENIED, HRESULT_ERROR_BUSY, E_FAIL] {
+            assert_eq!(
+                diagnose_hresult(hresult, ConsentState::Denied, Some(CM_PROB_FAILED_START)),
+                OpenFailureDiagnosis::DriverFailure {
+                    code: CM_PROB_FAILED_START
+                }
+            );
+        }
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn instance_id_from_symbolic_link_drops_the_interface() {
+        assert_eq!(
+            instance_id_from_symbolic_link(
+                "\\\\?\\USB#VID_046D&PID_085B&MI_00#7&1a2b&0&0000#{e5323777-f976-4f5b-9b55-b94699c46e44}\\GLOBAL"
+            )
+            .as_deref(),
+            Some("USB\\VID_046D&PID_085B&MI_00\\7&1a2b&0&0000")
+        );
+        assert_eq!(instance_id_from_symbolic_link("usb#vid_046d"), None);
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn open_failure_is_not_diagnosed_off_windows() {
+        let device = CameraDevice::from_device_path("\\\\?\\usb#vid_046d&pid_0825#1#{guid}");
+
+        assert_eq!(
+            diagnose_open_failure(&device),
+            Some(OpenFailureDiagnosis::Unknown { hresult: E_NOTIMPL })
+        );
+        assert_eq!(check_availability(&device), Availability::Unknown);
+    }
+}
@@ -0,0 +1,531 @@
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+use super::dedup::dedup_camera_devices;
+use super::device_enum::{CameraDevice, EnumerationSource};
+use super::error::EnumerationError;
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+use super::platform;
+
+/// A source of camera devices; implement it to plug in another backend or
+/// a mock.
+pub trait CameraEnumerator: Send + Sync {
+    fn enumerate(&self) -> Result<Vec<CameraDevice>, EnumerationError>;
+}
+
+#[cfg(windows)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WindowsMediaFoundationEnumerator;
+
+#[cfg(windows)]
+impl CameraEnumerator for WindowsMediaFoundationEnumerator {
+    fn enumerate(&self) -> Result<Vec<CameraDevice>, EnumerationError> {
+        super::platform::windows::enumerate_media_foundation_devices()
+    }
+}
+
+#[cfg(windows)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WindowsDirectShowEnumerator;
+
+#[cfg(windows)]
+impl CameraEnumerator for WindowsDirectShowEnumerator {
+    fn enumerate(&self) -> Result<Vec<CameraDevice>, EnumerationError> {
+        super::platform::windows::enumerate_directshow_devices()
+    }
+}
+
+/// Video capture interfaces from the registry's kernel streaming device
+/// classes; catches filter-driver cameras the other backends miss.
+#[cfg(windows)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WindowsRegistryEnumerator;
+
+#[cfg(windows)]
+impl CameraEnumerator for WindowsRegistryEnumerator {
+    fn enumerate(&self) -> Result<Vec<CameraDevice>, EnumerationError> {
+        Ok(super::platform::windows_registry::enumerate_registry_devices())
+    }
+}
+
+/// Camera interfaces listed by SetupAPI, with hardware IDs and driver
+/// details the other backends lack.
+#[cfg(windows)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WindowsSetupApiEnumerator;
+
+#[cfg(windows)]
+impl CameraEnumerator for WindowsSetupApiEnumerator {
+    fn enumerate(&self) -> Result<Vec<CameraDevice>, EnumerationError> {
+        Ok(super::platform::windows_setupapi::enumerate_setupapi_devices())
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct V4l2Enumerator;
+
+#[cfg(target_os = "linux")]
+impl CameraEnumerator for V4l2Enumerator {
+    fn enumerate(&self) -> Result<Vec<CameraDevice>, EnumerationError> {
+        Ok(platform::linux::enumerate_linux_devices())
+    }
+}
+
+#[cfg(target_os = "macos")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AvFoundationEnumerator;
+
+#[cfg(target_os = "macos")]
+impl CameraEnumerator for AvFoundationEnumerator {
+    fn enumerate(&self) -> Result<Vec<CameraDevice>, EnumerationError> {
+        Ok(platform::macos::enumerate_macos_devices())
+    }
+}
+
+/// Returns a fixed list of devices, for exercising detection without
+/// hardware. Enabled by the `testing` feature and in this crate's tests.
+#[cfg(any(test, feature = "testing"))]
+#[derive(Debug, Clone, Default)]
+pub struct MockEnumerator(pub Vec<CameraDevice>);
+
+#[cfg(any(test, feature = "testing"))]
+impl MockEnumerator {
+    pub fn new(devices: Vec<CameraDevice>) -> Self {
+        Self(devices)
+    }
+}
+
+#[cfg(any(test, feature = "testing"))]
+impl CameraEnumerator for MockEnumerator {
+    fn enumerate(&self) -> Result<Vec<CameraDevice>, EnumerationError> {
+        Ok(self.0.clone())
+    }
+}
+
+/// Runs every enumerator and merges their devices, deduplicated.
+///
+/// When only some enumerators fail, the result is `EnumerationError::Partial`
+/// with the devices of the others; when all fail, the first error. With the
+/// `parallel` feature the enumerators run on separate threads.
+pub struct CompositeEnumerator(pub Vec<Box<dyn CameraEnumerator>>);
+
+impl CompositeEnumerator {
+    #[cfg(not(feature = "parallel"))]
+    fn run_all(&self) -> Vec<Result<Vec<CameraDevice>, EnumerationError>> {
+        // Held across the backends so COM is not torn down between them.
+        #[cfg(windows)]
+        let _com = (!self.0.is_empty())
+            .then(super::platform::windows::ComApartment::enter)
+            .and_then(Result::ok);
+
+        self.0
+            .iter()
+            .map(|enumerator| enumerator.enumerate())
+            .collect()
+    }
+
+    // One thread per enumerator; each backend initializes COM on its own.
+    #[cfg(feature = "parallel")]
+    fn run_all(&self) -> Vec<Result<Vec<CameraDevice>, EnumerationError>> {
+        std::thread::scope(|scope| {
+            let threads = self
+                .0
+                .iter()
+                .map(|enumerator| scope.spawn(|| enumerator.enumerate()))
+                .collect::<Vec<_>>();
+            threads
+                .into_iter()
+                .map(|thread| {
+                    thread
+                        .join()
+                        .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+                })
+                .collect()
+        })
+    }
+}
+
+impl CompositeEnumerator {
+    /// Like `enumerate`, but keeps every backend's record of a device
+    /// instead of merging them.
+    pub(crate) fn enumerate_raw(&self) -> Result<Vec<CameraDevice>, EnumerationError> {
+        collect_results(self.run_all())
+    }
+}
+
+impl CameraEnumerator for CompositeEnumerator {
+    fn enumerate(&self) -> Result<Vec<CameraDevice>, EnumerationError> {
+        merge_results(self.run_all())
+    }
+}
+
+fn merge_results(
+    results: Vec<Result<Vec<CameraDevice>, EnumerationError>>,
+) -> Result<Vec<CameraDevice>, EnumerationError> {
+    match collect_results(results) {
+        Ok(devices) => Ok(dedup_camera_devices(devices)),
+        Err(EnumerationError::Partial(devices, error)) => Err(EnumerationError::Partial(
+            dedup_camera_devices(devices),
+            error,
+        )),
+        Err(error) => Err(error),
+    }
+}
+
+// Partial when only some backends failed, the first error when all did.
+fn collect_results(
+    results: Vec<Result<Vec<CameraDevice>, EnumerationError>>,
+) -> Result<Vec<CameraDevice>, EnumerationError> {
+    let mut devices = Vec::new();
+    let mut first_error = None;
+    let mut any_succeeded = results.is_empty();
+    for result in results {
+        match result {
+            Ok(found) => {
+                devices.extend(found);
+                any_succeeded = true;
+            }
+            Err(EnumerationError::Partial(found, error)) => {
+                devices.extend(found);
+                any_succeeded = true;
+                first_error.get_or_insert(*error);
+            }
+            Err(error) => {
+                first_error.get_or_insert(error);
+            }
+        }
+    }
+
+    match first_error {
+        None => Ok(devices),
+        Some(error) if any_succeeded => Err(EnumerationError::Partial(devices, Box::new(error))),
+        Some(error) => Err(error),
+    }
+}
+
+/// Which backends to enumerate with; all are enabled by default.
+///
+/// The switches only apply on Windows; other platforms have one backend,
+/// which always runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DetectionOptions {
+    pub media_foundation: bool,
+    pub directshow: bool,
+    pub registry: bool,
+    pub setupapi: bool,
+}
+
+impl Default for DetectionOptions {
+    fn default() -> Self {
+        Self {
+            media_foundation: true,
+            directshow: true,
+            registry: true,
+            setupapi: true,
+        }
+    }
+}
+
+impl DetectionOptions {
+    pub fn with_media_foundation(mut self, media_foundation: bool) -> Self {
+        self.media_foundation = media_foundation;
+        self
+    }
+
+    pub fn with_directshow(mut self, directshow: bool) -> Self {
+        self.directshow = directshow;
+        self
+    }
+
+    pub fn with_registry(mut self, registry: bool) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    pub fn with_setupapi(mut self, setupapi: bool) -> Self {
+        self.setupapi = setupapi;
+        self
+    }
+
+    /// The enabled backends, combined. A disabled backend makes no COM calls.
+    pub fn enumerator(&self) -> Box<dyn CameraEnumerator> {
+        Box::new(self.composite())
+    }
+
+    pub(crate) fn composite(&self) -> CompositeEnumerator {
+        CompositeEnumerator(
+            self.backends()
+                .into_iter()
+                .map(|(_, enumerator)| enumerator)
+                .collect(),
+        )
+    }
+
+    fn backends(&self) -> Vec<(EnumerationSource, Box<dyn CameraEnumerator>)> {
+        #[cfg(windows)]
+        let backends = [
+            (
+                self.media_foundation,
+                EnumerationSource::MediaFoundation,
+                Box::new(WindowsMediaFoundationEnumerator) as Box<dyn CameraEnumerator>,
+            ),
+            (
+                self.directshow,
+                EnumerationSource::DirectShow,
+                Box::new(WindowsDirectShowEnumerator),
+            ),
+            (
+                self.registry,
+                EnumerationSource::Registry,
+                Box::new(WindowsRegistryEnumerator),
+            ),
+            (
+                self.setupapi,
+                EnumerationSource::SetupApi,
+                Box::new(WindowsSetupApiEnumerator),
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(enabled, source, enumerator)| enabled.then_some((source, enumerator)))
+        .collect();
+        #[cfg(not(windows))]
+        let backends: Vec<(EnumerationSource, Box<dyn CameraEnumerator>)> = vec![
+            #[cfg(target_os = "linux")]
+            (EnumerationSource::V4l2, Box::new(V4l2Enumerator)),
+            #[cfg(target_os = "macos")]
+            (
+                EnumerationSource::AvFoundation,
+                Box::new(AvFoundationEnumerator),
+            ),
+        ];
+        backends
+    }
+}
+
+/// The backends of the current platform, combined.
+pub fn default_enumerator() -> Box<dyn CameraEnumerator> {
+    DetectionOptions::default().enumerator()
+}
+
+pub fn enumerate_devices_with_options(
+    options: &DetectionOptions,
+) -> Result<Vec<CameraDevice>, EnumerationError> {
+    enumerate_with(options.enumerator().as_ref())
+}
+
+/// `enumerate_devices` that gives up once `timeout` has passed, for drivers
+/// that hang inside Media Foundation or DirectShow.
+///
+/// The backends run one after another on a new thread with its own COM
+/// apartment. When the deadline passes first, the result is
+/// `EnumerationError::Timeout` with the devices of the backends that
+/// finished. The thread is then abandoned: it shares no COM state or locks
+/// with the caller, skips the remaining backends, and balances its own
+/// Media Foundation startup if the stuck call ever returns, so later
+/// enumerations are unaffected. A call that never returns keeps its thread
+/// blocked for the life of the process.
+///
+/// When the thread cannot be started, the result is
+/// `EnumerationError::DeviceEnumFailed` for the first backend, with `E_FAIL`.
+pub fn enumerate_devices_with_timeout(
+    timeout: Duration,
+) -> Result<Vec<CameraDevice>, EnumerationError> {
+    enumerate_backends_with_timeout(DetectionOptions::default().backends(), timeout)
+}
+
+fn enumerate_backends_with_timeout(
+    backends: Vec<(EnumerationSource, Box<dyn CameraEnumerator>)>,
+    timeout: Duration,
+) -> Result<Vec<CameraDevice>, EnumerationError> {
+    const E_FAIL: i32 = 0x8000_4005_u32 as i32;
+
+    let sources = backends
+        .iter()
+        .map(|(source, _)| *source)
+        .collect::<Vec<_>>();
+    let Some(&first_backend) = sources.first() else {
+        return Ok(Vec::new());
+    };
+
+    let (sender, receiver) = mpsc::channel();
+    let thread = std::thread::Builder::new()
+        .name("camera-enumeration-timeout".to_string())
+        .spawn(move || {
+            #[cfg(windows)]
+            let _com = (!backends.is_empty())
+                .then(super::platform::windows::ComApartment::enter)
+                .and_then(Result::ok);
+            for (_, enumerator) in backends {
+                // Stop once the caller has given up.
+                if sender.send(enumerator.enumerate()).is_err() {
+                    break;
+                }
+            }
+        })
+        .map_err(|_| EnumerationError::DeviceEnumFailed {
+            backend: first_backend,
+            hresult: E_FAIL,
+        })?;
+
+    // A timeout too large to represent waits indefinitely.
+    let deadline = Instant::now().checked_add(timeout);
+    let mut results = Vec::new();
+    while let Some(&backend) = sources.get(results.len()) {
+        let received = match deadline {
+            Some(deadline) => {
+                receiver.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+            }
+            None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        match received {
+            Ok(result) => results.push(result),
+            Err(RecvTimeoutError::Timeout) => {
+                let devices =
+                    merge_results(results).unwrap_or_else(EnumerationError::into_partial_devices);
+                return Err(EnumerationError::Timeout { backend, devices });
+            }
+            // Only a panicking backend drops the sender early.
+            Err(RecvTimeoutError::Disconnected) => {
+                if let Err(panic) = thread.join() {
+                    std::panic::resume_unwind(panic);
+                }
+                break;
+            }
+        }
+    }
+    merge_results(results)
+}
+
+pub fn enumerate_with(
+    enumerator: &dyn CameraEnumerator,
+) -> Result<Vec<CameraDevice>, EnumerationError> {
+    enumerator.enumerate()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::camera::detector::Detector;
+    use crate::camera::device_enum::DetectionResult;
+
+    const USB_PATH: &str =
+        r"\\?\usb#vid_046d&pid_082d#A1B2C3#{e5323777-f976-4f5b-9b55-b94699c46e44}\global";
+
+    struct FailingEnumerator;
+
+    impl CameraEnumerator for FailingEnumerator {
+        fn enumerate(&self) -> Result<Vec<CameraDevice>, EnumerationError> {
+            Err(EnumerationError::DeviceEnumFailed {
+                backend: EnumerationSource::DirectShow,
+                hresult: -1,
+            })
+        }
+    }
+
+    struct SleepingEnumerator(Duration, Vec<CameraDevice>);
+
+    impl CameraEnumerator for SleepingEnumerator {
+        fn enumerate(&self) -> Result<Vec<CameraDevice>, EnumerationError> {
+            std::thread::sleep(self.0);
+            Ok(self.1.clone())
+        }
+    }
+
+    fn devices() -> Vec<CameraDevice> {
+        vec![
+            CameraDevice::from_name_and_path("Logitech C920", USB_PATH),
+            CameraDevice::from_name_and_path("OBS Virtual Camera", ""),
+        ]
+    }
+
+    #[test]
+    fn detector_uses_the_mock_devices() {
+        let detector =
+            Detector::default().with_enumerator(Box::new(MockEnumerator::new(devices())));
+
+        assert_eq!(detector.enumerate().unwrap().len(), 2);
+        assert_eq!(
+            detector.detect().unwrap(),
+            DetectionResult::Mixed {
+                real_count: 1,
+                virtual_count: 1,
+            }
+        );
+
+        let empty = Detector::builder()
+            .enumerator(Box::new(MockEnumerator::default()))
+            .build();
+        assert_eq!(empty.detect().unwrap(), DetectionResult::NoCamera);
+    }
+
+    #[test]
+    fn composite_merges_and_deduplicates() {
+        let composite = CompositeEnumerator(vec![
+            Box::new(MockEnumerator::new(devices())),
+            Box::new(MockEnumerator::new(devices()[..1].to_vec())),
+        ]);
+
+        assert_eq!(composite.enumerate().unwrap().len(), 2);
+        assert_eq!(composite.enumerate_raw().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn composite_keeps_devices_when_a_backend_fails() {
+        let composite = CompositeEnumerator(vec![
+            Box::new(FailingEnumerator),
+            Box::new(MockEnumerator::new(devices())),
+        ]);
+        match composite.enumerate() {
+            Err(EnumerationError::Partial(devices, error)) => {
+                assert_eq!(devices.len(), 2);
+                assert_eq!(error.backend(), EnumerationSource::DirectShow);
+            }
+            other => panic!("expected a partial result, got {other:?}"),
+        }
+
+        let composite = CompositeEnumerator(vec![Box::new(FailingEnumerator)]);
+        assert!(matches!(
+            composite.enumerate(),
+            Err(EnumerationError::DeviceEnumFailed { .. })
+        ));
+    }
+
+    #[test]
+    fn a_hanging_backend_times_out_with_the_earlier_devices() {
+        let backends: Vec<(EnumerationSource, Box<dyn CameraEnumerator>)> = vec![
+            (
+                EnumerationSource::MediaFoundation,
+                Box::new(MockEnumerator::new(devices())),
+            ),
+            (
+                EnumerationSource::DirectShow,
+                Box::new(SleepingEnumerator(Duration::from_secs(30), Vec::new())),
+            ),
+        ];
+
+        let started = Instant::now();
+        let result = enumerate_backends_with_timeout(backends, Duration::from_millis(100));
+
+        assert!(started.elapsed() < Duration::from_secs(5));
+        match result {
+            Err(EnumerationError::Timeout { backend, devices }) => {
+                assert_eq!(backend, EnumerationSource::DirectShow);
+                assert_eq!(devices.len(), 2);
+            }
+            other => panic!("expected a timeout, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn slow_backends_within_the_deadline_succeed() {
+        let backends: Vec<(EnumerationSource, Box<dyn CameraEnumerator>)> = vec![(
+            EnumerationSource::MediaFoundation,
+            Box::new(SleepingEnumerator(Duration::from_millis(20), devices())),
+        )];
+
+        let devices = enumerate_backends_with_timeout(backends, Duration::MAX).unwrap();
+
+        assert_eq!(devices.len(), 2);
+    }
+}
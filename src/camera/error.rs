@@ -0,0 +1,128 @@
+use std::fmt;
+
+use super::device_enum::{CameraDevice, EnumerationSource};
+
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum EnumerationError {
+    ComInitFailed {
+        backend: EnumerationSource,
+        hresult: i32,
+    },
+    MediaFoundationStartupFailed {
+        hresult: i32,
+    },
+    AttributeCreationFailed {
+        hresult: i32,
+    },
+    /// `CoCreateInstance` could not create the DirectShow system device enumerator.
+    NoEnumeratorCreated {
+        hresult: i32,
+    },
+    /// The enumeration call itself failed.
+    DeviceEnumFailed {
+        backend: EnumerationSource,
+        hresult: i32,
+    },
+    /// Some backends failed; the devices from the ones that succeeded are kept.
+    Partial(Vec<CameraDevice>, Box<EnumerationError>),
+    /// `backend` was still running when the deadline passed; `devices` holds
+    /// what the backends before it found.
+    Timeout {
+        backend: EnumerationSource,
+        devices: Vec<CameraDevice>,
+    },
+}
+
+// HRESULT_FROM_WIN32(ERROR_TIMEOUT), reported for timeouts so every error
+// has an HRESULT.
+const TIMEOUT_HRESULT: i32 = 0x8007_05b4_u32 as i32;
+
+impl EnumerationError {
+    /// The backend that failed; for `Partial`, the one that failed first.
+    pub fn backend(&self) -> EnumerationSource {
+        match self {
+            EnumerationError::ComInitFailed { backend, .. }
+            | EnumerationError::DeviceEnumFailed { backend, .. }
+            | EnumerationError::Timeout { backend, .. } => *backend,
+            EnumerationError::MediaFoundationStartupFailed { .. }
+            | EnumerationError::AttributeCreationFailed { .. } => {
+                EnumerationSource::MediaFoundation
+            }
+            EnumerationError::NoEnumeratorCreated { .. } => EnumerationSource::DirectShow,
+            EnumerationError::Partial(_, error) => error.backend(),
+        }
+    }
+
+    pub fn hresult(&self) -> i32 {
+        match self {
+            EnumerationError::ComInitFailed { hresult, .. }
+            | EnumerationError::MediaFoundationStartupFailed { hresult }
+            | EnumerationError::AttributeCreationFailed { hresult }
+            | EnumerationError::NoEnumeratorCreated { hresult }
+            | EnumerationError::DeviceEnumFailed { hresult, .. } => *hresult,
+            EnumerationError::Partial(_, error) => error.hresult(),
+            EnumerationError::Timeout { .. } => TIMEOUT_HRESULT,
+        }
+    }
+
+    pub fn partial_devices(&self) -> &[CameraDevice] {
+        match self {
+            EnumerationError::Partial(devices, _) | EnumerationError::Timeout { devices, .. } => {
+                devices
+            }
+            _ => &[],
+        }
+    }
+
+    pub fn into_partial_devices(self) -> Vec<CameraDevice> {
+        match self {
+            EnumerationError::Partial(devices, _) | EnumerationError::Timeout { devices, .. } => {
+                devices
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl fmt::Display for EnumerationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnumerationError::ComInitFailed { .. } => write!(f, "COM initialization failed")?,
+            EnumerationError::MediaFoundationStartupFailed { .. } => {
+                write!(f, "Media Foundation startup failed")?
+            }
+            EnumerationError::AttributeCreationFailed { .. } => {
+                write!(f, "could not create device source attributes")?
+            }
+            EnumerationError::NoEnumeratorCreated { .. } => {
+                write!(f, "could not create a device enumerator")?
+            }
+            EnumerationError::DeviceEnumFailed { .. } => write!(f, "device enumeration failed")?,
+            EnumerationError::Timeout { .. } => write!(f, "enumeration timed out")?,
+            EnumerationError::Partial(devices, source) => {
+                return write!(
+                    f,
+                    "enumeration partially failed ({} devices found): {source}",
+                    devices.len()
+                );
+            }
+        }
+
+        write!(
+            f,
+            " ({:?}, HRESULT {:#010x})",
+            self.backend(),
+            self.hresult()
+        )
+    }
+}
+
+impl std::error::Error for EnumerationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EnumerationError::Partial(_, source) => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
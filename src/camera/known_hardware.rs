@@ -0,0 +1,59 @@
+/// USB vendor IDs of webcam makers whose USB devices are treated as real
+/// without checking the other rules, unless a VID/PID rule in the config
+/// names the device itself. They seed `DetectionConfig::known_real_vids`.
+///
+/// | VID    | Vendor                               |
+/// |--------|--------------------------------------|
+/// | `046d` | Logitech                             |
+/// | `045e` | Microsoft                            |
+/// | `05ac` | Apple                                |
+/// | `04f2` | Chicony Electronics                  |
+/// | `0c45` | Microdia (Sonix)                     |
+/// | `13d3` | IMC Networks (AzureWave)             |
+/// | `5986` | Bison Electronics (Acer, Lenovo OEM) |
+/// | `174f` | Syntek                               |
+/// | `1bcf` | Sunplus Innovation                   |
+/// | `0bda` | Realtek                              |
+/// | `04ca` | Lite-On                              |
+/// | `1415` | Nam Tai (Sony PlayStation Eye)       |
+/// | `2bd9` | Elgato                               |
+/// | `1532` | Razer                                |
+pub const KNOWN_REAL_VIDS: &[&str] = &[
+    "046d", "045e", "05ac", "04f2", "0c45", "13d3", "5986", "174f", "1bcf", "0bda", "04ca", "1415",
+    "2bd9", "1532",
+];
+
+/// Whether `vid` is in `KNOWN_REAL_VIDS`, ignoring case.
+pub fn is_known_real_vendor(vid: &str) -> bool {
+    KNOWN_REAL_VIDS
+        .iter()
+        .any(|known| known.eq_ignore_ascii_case(vid))
+}
+
+// Names for USB vendor IDs commonly seen on cameras.
+const VENDOR_NAMES: [(u16, &str); 16] = [
+    (0x041e, "Creative Technology"),
+    (0x045e, "Microsoft"),
+    (0x046d, "Logitech"),
+    (0x04ca, "Lite-On"),
+    (0x04f2, "Chicony Electronics"),
+    (0x05ac, "Apple"),
+    (0x0ac8, "Z-Star Microelectronics"),
+    (0x0bda, "Realtek"),
+    (0x0c45, "Sonix"),
+    (0x13d3, "IMC Networks"),
+    (0x1415, "Nam Tai"),
+    (0x1532, "Razer"),
+    (0x174f, "Syntek"),
+    (0x1bcf, "Sunplus Innovation"),
+    (0x2bd9, "Elgato"),
+    (0x5986, "Bison Electronics"),
+];
+
+/// The name of a well-known camera vendor.
+pub fn vendor_name(vid: u16) -> Option<&'static str> {
+    VENDOR_NAMES
+        .iter()
+        .find(|(known, _)| *known == vid)
+        .map(|(_, name)| *name)
+}
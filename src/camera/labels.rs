@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+
+use super::device_enum::CameraDevice;
+
+type Qualifier = fn(&CameraDevice) -> Option<String>;
+
+const QUALIFIERS: [Qualifier; 2] = [vid_pid_qualifier, serial_qualifier];
+
+pub fn display_labels(devices: &[CameraDevice]) -> Vec<String> {
+    let mut labels = devices
+        .iter()
+        .map(|device| device.name.clone())
+        .collect::<Vec<_>>();
+
+    for qualifier in QUALIFIERS {
+        let colliding = colliding_indices(&labels);
+        if colliding.is_empty() {
+            return labels;
+        }
+        for index in colliding {
+            if let Some(extra) = qualifier(&devices[index]) {
+                labels[index] = format!("{} ({extra})", labels[index]);
+            }
+        }
+    }
+
+    let mut ordinals = HashMap::new();
+    for index in colliding_indices(&labels) {
+        let ordinal = ordinals.entry(labels[index].clone()).or_insert(0);
+        *ordinal += 1;
+        labels[index] = format!("{} #{ordinal}", labels[index]);
+    }
+
+    labels
+}
+
+fn colliding_indices(labels: &[String]) -> Vec<usize> {
+    let mut counts = HashMap::new();
+    for label in labels {
+        *counts.entry(label.as_str()).or_insert(0) += 1;
+    }
+
+    labels
+        .iter()
+        .enumerate()
+        .filter(|(_, label)| counts[label.as_str()] > 1)
+        .map(|(index, _)| index)
+        .collect()
+}
+
+fn vid_pid_qualifier(device: &CameraDevice) -> Option<String> {
+    match (&device.vid, &device.pid) {
+        (Some(vid), Some(pid)) => Some(format!("{vid}:{pid}")),
+        _ => None,
+    }
+}
+
+fn serial_qualifier(device: &CameraDevice) -> Option<String> {
+    device
+        .serial_number
+        .as_ref()
+        .map(|serial| format!("S/N {serial}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn device(name: &str, path: &str) -> CameraDevice {
+        CameraDevice::from_name_and_path(name, path)
+    }
+
+    #[test]
+    fn unique_names_are_labels() {
+        let devices = [
+            device(
+                "Integrated Camera",
+                "\\\\?\\usb#vid_04f2&pid_b6dd&mi_00#6&1#{guid}",
+            ),
+            device("OBS Virtual Camera", ""),
+        ];
+
+        assert_eq!(
+            display_labels(&devices),
+            ["Integrated Camera", "OBS Virtual Camera"]
+        );
+    }
+
+    #[test]
+    fn only_colliding_names_are_qualified() {
+        let devices = [
+            device(
+                "Integrated Camera",
+                "\\\\?\\usb#vid_04f2&pid_b6dd&mi_00#6&1#{guid}",
+            ),
+            device("USB Camera", "\\\\?\\usb#vid_046d&pid_0825#A1#{guid}"),
+            device("USB Camera", "\\\\?\\usb#vid_1234&pid_0001#B2#{guid}"),
+            device("HD Webcam", "\\\\?\\usb#vid_046d&pid_085b#C3#{guid}"),
+            device("HD Webcam", "\\\\?\\usb#vid_046d&pid_085b#D4#{guid}"),
+            device("Virtual Camera", ""),
+            device("Virtual Camera", ""),
+        ];
+
+        assert_eq!(
+            display_labels(&devices),
+            [
+                "Integrated Camera",
+                "USB Camera (046D:0825)",
+                "USB Camera (1234:0001)",
+                "HD Webcam (046D:085B) (S/N C3)",
+                "HD Webcam (046D:085B) (S/N D4)",
+                "Virtual Camera #1",
+                "Virtual Camera #2",
+            ]
+        );
+    }
+}
@@ -1,5 +1,91 @@
-pub mod device_enum;
+mod cache;
+mod calibration;
+mod capabilities;
+mod config;
+mod connection;
+mod dedup;
+mod detector;
+mod device_enum;
+mod diagnosis;
+mod enumerator;
+mod error;
+mod known_hardware;
+mod labels;
+mod platform;
+mod probe;
+mod remote_control;
+#[cfg(feature = "serde")]
+mod rules;
+mod scoring;
+mod self_test;
+mod snapshot;
+mod watch;
+#[cfg(feature = "tokio")]
+mod worker;
 
+pub use cache::CachedDetector;
+pub use calibration::{
+    calibrate, current_os_build, derive_budgets, needs_recalibration, CalibrationProfile,
+    PipelineStage, StageBudgets,
+};
+pub use capabilities::{
+    enumerate_devices_with_formats, query_capabilities, CameraCapabilities, CameraMode,
+    CapabilityError, DeviceFormats,
+};
+#[cfg(feature = "serde")]
+pub use config::ConfigParseError;
+pub use config::{DetectionConfig, Whitelist};
+pub use connection::{connection_kind, CameraConnection};
+pub use dedup::{dedup_camera_devices, merge_backend_devices};
+pub use detector::{Detector, DetectorBuilder};
+#[allow(deprecated)]
+pub use device_enum::detect_cameras;
+#[cfg(feature = "serde")]
+pub use device_enum::enumerate_devices_json;
 pub use device_enum::{
-    detect_cameras, enumerate_devices, CameraDevice, DetectionResult,
+    classify, classify_device, classify_devices, classify_devices_with_reason, classify_reason,
+    classify_stream, classify_with, detect_cameras_checked, detect_cameras_detailed,
+    detect_cameras_from, detect_cameras_with, enumerate_devices, enumerate_devices_filtered,
+    enumerate_devices_from, enumerate_devices_iter, enumerate_devices_raw,
+    enumerate_devices_with_whitelist, enumerate_real_cameras, enumerate_real_cameras_with,
+    enumerate_virtual_cameras, enumerate_virtual_cameras_with, has_virtual_camera,
+    has_virtual_camera_from, has_virtual_camera_with_config, is_network_camera, is_virtual_camera,
+    normalize_device_path, score_device, CameraDevice, Classification, DetectionReason,
+    DetectionResult, DetectionSummary, DeviceKind, EnumerationSource,
+};
+#[cfg(feature = "tokio")]
+pub use device_enum::{detect_cameras_async, enumerate_devices_async};
+pub use diagnosis::{
+    camera_access_status, check_availability, diagnose_hresult, diagnose_open_failure,
+    Availability, CameraAccessStatus, ConsentState, OpenFailureDiagnosis,
+};
+#[cfg(target_os = "macos")]
+pub use enumerator::AvFoundationEnumerator;
+#[cfg(feature = "testing")]
+pub use enumerator::MockEnumerator;
+#[cfg(target_os = "linux")]
+pub use enumerator::V4l2Enumerator;
+pub use enumerator::{
+    default_enumerator, enumerate_devices_with_options, enumerate_devices_with_timeout,
+    enumerate_with, CameraEnumerator, CompositeEnumerator, DetectionOptions,
+};
+#[cfg(windows)]
+pub use enumerator::{
+    WindowsDirectShowEnumerator, WindowsMediaFoundationEnumerator, WindowsRegistryEnumerator,
+    WindowsSetupApiEnumerator,
+};
+pub use error::EnumerationError;
+pub use known_hardware::{is_known_real_vendor, vendor_name, KNOWN_REAL_VIDS};
+pub use labels::display_labels;
+pub use probe::probe_live;
+pub use remote_control::{
+    detect_session_context, match_remote_control_processes, remote_control_report,
+    remote_control_report_from, virtual_camera_kind, RemoteControlReport, SessionContext,
+    VirtualCameraKind,
 };
+#[cfg(feature = "serde")]
+pub use rules::{Rules, RulesMode};
+pub use scoring::{classify_scored, ScoreContribution, ScoreSignal, ScoreWeights, VirtualScore};
+pub use self_test::{self_test, self_test_with, SelfTestFailure, SelfTestReport};
+pub use snapshot::{diff, CameraSnapshot, CameraSnapshotDiff};
+pub use watch::{watch, watch_devices, CameraWatcher, DeviceEvent, DeviceWatcher, WatchError};
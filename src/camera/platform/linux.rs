@@ -0,0 +1,197 @@
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use crate::camera::device_enum::{CameraDevice, EnumerationSource};
+use crate::camera::platform::log_device;
+
+const SYSFS_VIDEO4LINUX: &str = "/sys/class/video4linux";
+
+const V4L2_CAP_VIDEO_CAPTURE: u32 = 0x0000_0001;
+const V4L2_CAP_VIDEO_CAPTURE_MPLANE: u32 = 0x0000_1000;
+const V4L2_CAP_DEVICE_CAPS: u32 = 0x8000_0000;
+
+const V4L2_CID_CAMERA_ORIENTATION: u32 = 0x009a_0922;
+const V4L2_CID_CAMERA_SENSOR_ROTATION: u32 = 0x009a_0923;
+const V4L2_CAMERA_ORIENTATION_FRONT: i32 = 0;
+const V4L2_CAMERA_ORIENTATION_BACK: i32 = 1;
+
+pub(crate) fn enumerate_linux_devices() -> Vec<CameraDevice> {
+    let mut nodes = video_node_names(Path::new("/dev"));
+    nodes.extend(video_node_names(Path::new(SYSFS_VIDEO4LINUX)));
+    nodes.sort_by_key(|node| node["video".len()..].parse::<u32>().unwrap_or(u32::MAX));
+    nodes.dedup();
+
+    nodes
+        .into_iter()
+        .filter_map(|node| {
+            let path = Path::new("/dev").join(&node);
+            let file = open_video_node(&path)?;
+            let capability = query_v4l2_capability(&file)?;
+            // UVC cameras expose a second node for the same device that only
+            // advertises V4L2_CAP_META_CAPTURE.
+            if !capability.is_video_capture() {
+                return None;
+            }
+            let mirrored = match query_v4l2_control(&file, V4L2_CID_CAMERA_ORIENTATION) {
+                Some(V4L2_CAMERA_ORIENTATION_FRONT) => Some(true),
+                Some(V4L2_CAMERA_ORIENTATION_BACK) => Some(false),
+                _ => None,
+            };
+            let usb = find_usb_device_dir(&node);
+            let read_usb = |attribute: &str| {
+                usb.as_deref()
+                    .and_then(|dir| read_sysfs_attribute(&dir.join(attribute)))
+            };
+
+            let name = if capability.card.is_empty() {
+                read_sysfs_attribute(&Path::new(SYSFS_VIDEO4LINUX).join(&node).join("name"))
+                    .unwrap_or_else(|| node.clone())
+            } else {
+                capability.card
+            };
+
+            Some(CameraDevice {
+                name,
+                manufacturer: read_usb("manufacturer"),
+                device_path: Some(path.to_string_lossy().into_owned()),
+                driver: Some(capability.driver),
+                vid: read_usb("idVendor").map(|vid| vid.to_uppercase()),
+                pid: read_usb("idProduct").map(|pid| pid.to_uppercase()),
+                clsid: None,
+                serial_number: read_usb("serial"),
+                orientation: query_v4l2_control(&file, V4L2_CID_CAMERA_SENSOR_ROTATION),
+                mirrored,
+                built_in: None,
+                hardware_ids: Vec::new(),
+                instance_id: None,
+                enumeration_source: EnumerationSource::V4l2,
+            })
+        })
+        .inspect(log_device)
+        .collect()
+}
+
+struct V4l2Capability {
+    driver: String,
+    card: String,
+    device_caps: u32,
+}
+
+impl V4l2Capability {
+    fn is_video_capture(&self) -> bool {
+        self.device_caps & (V4L2_CAP_VIDEO_CAPTURE | V4L2_CAP_VIDEO_CAPTURE_MPLANE) != 0
+    }
+}
+
+fn video_node_names(dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| {
+            name.strip_prefix("video")
+                .is_some_and(|index| !index.is_empty() && index.bytes().all(|b| b.is_ascii_digit()))
+        })
+        .collect()
+}
+
+fn find_usb_device_dir(node: &str) -> Option<PathBuf> {
+    let device = Path::new(SYSFS_VIDEO4LINUX)
+        .join(node)
+        .join("device")
+        .canonicalize()
+        .ok()?;
+
+    // The video node hangs off a USB interface; idVendor lives on its parent device.
+    device
+        .ancestors()
+        .take(3)
+        .find(|dir| dir.join("idVendor").is_file())
+        .map(Path::to_path_buf)
+}
+
+fn read_sysfs_attribute(path: &Path) -> Option<String> {
+    let value = std::fs::read_to_string(path).ok()?;
+    let value = value.trim();
+    if value.is_empty() {
+        None
+    } else {
+        Some(value.to_string())
+    }
+}
+
+fn open_video_node(path: &Path) -> Option<File> {
+    use std::os::unix::fs::OpenOptionsExt;
+
+    std::fs::OpenOptions::new()
+        .read(true)
+        .custom_flags(libc::O_NONBLOCK)
+        .open(path)
+        .ok()
+}
+
+fn query_v4l2_capability(file: &File) -> Option<V4l2Capability> {
+    use std::os::unix::io::AsRawFd;
+
+    #[repr(C)]
+    struct RawV4l2Capability {
+        driver: [u8; 16],
+        card: [u8; 32],
+        bus_info: [u8; 32],
+        version: u32,
+        capabilities: u32,
+        device_caps: u32,
+        reserved: [u32; 3],
+    }
+
+    // _IOR('V', 0, struct v4l2_capability)
+    const VIDIOC_QUERYCAP: libc::c_ulong = 0x8068_5600;
+
+    let mut raw = std::mem::MaybeUninit::<RawV4l2Capability>::zeroed();
+    let result = unsafe { libc::ioctl(file.as_raw_fd(), VIDIOC_QUERYCAP as _, raw.as_mut_ptr()) };
+    if result < 0 {
+        return None;
+    }
+    let raw = unsafe { raw.assume_init() };
+
+    let device_caps = if raw.capabilities & V4L2_CAP_DEVICE_CAPS != 0 {
+        raw.device_caps
+    } else {
+        raw.capabilities
+    };
+
+    Some(V4l2Capability {
+        driver: c_bytes_to_string(&raw.driver),
+        card: c_bytes_to_string(&raw.card),
+        device_caps,
+    })
+}
+
+fn query_v4l2_control(file: &File, id: u32) -> Option<i32> {
+    use std::os::unix::io::AsRawFd;
+
+    #[repr(C)]
+    struct V4l2Control {
+        id: u32,
+        value: i32,
+    }
+
+    // _IOWR('V', 27, struct v4l2_control)
+    const VIDIOC_G_CTRL: libc::c_ulong = 0xc008_561b;
+
+    let mut control = V4l2Control { id, value: 0 };
+    let result = unsafe { libc::ioctl(file.as_raw_fd(), VIDIOC_G_CTRL as _, &mut control) };
+    if result < 0 {
+        None
+    } else {
+        Some(control.value)
+    }
+}
+
+fn c_bytes_to_string(bytes: &[u8]) -> String {
+    let length = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..length]).into_owned()
+}
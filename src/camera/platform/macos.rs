@@ -0,0 +1,120 @@
+use objc2_av_foundation::{
+    AVAuthorizationStatus, AVCaptureDevice, AVCaptureDeviceDiscoverySession,
+    AVCaptureDevicePosition, AVCaptureDeviceTypeBuiltInWideAngleCamera,
+    AVCaptureDeviceTypeContinuityCamera, AVCaptureDeviceTypeExternal, AVMediaTypeVideo,
+};
+use objc2_foundation::NSArray;
+
+use crate::camera::device_enum::{CameraDevice, EnumerationSource};
+use crate::camera::platform::log_device;
+
+pub(crate) fn enumerate_macos_devices() -> Vec<CameraDevice> {
+    unsafe {
+        let Some(media_type) = AVMediaTypeVideo else {
+            return Vec::new();
+        };
+
+        let status = AVCaptureDevice::authorizationStatusForMediaType(media_type);
+        if status == AVAuthorizationStatus::Denied || status == AVAuthorizationStatus::Restricted {
+            return Vec::new();
+        }
+
+        let device_types = NSArray::from_slice(&[
+            AVCaptureDeviceTypeBuiltInWideAngleCamera,
+            AVCaptureDeviceTypeExternal,
+            AVCaptureDeviceTypeContinuityCamera,
+        ]);
+        let session =
+            AVCaptureDeviceDiscoverySession::discoverySessionWithDeviceTypes_mediaType_position(
+                &device_types,
+                Some(media_type),
+                AVCaptureDevicePosition::Unspecified,
+            );
+
+        session
+            .devices()
+            .iter()
+            .map(|device| {
+                let unique_id = device.uniqueID().to_string();
+                let manufacturer = device.manufacturer().to_string();
+                // DAL plugins and camera extensions report their bundle identifier
+                // or plugin name here, which is what virtual detection keys on.
+                let model_id = device.modelID().to_string();
+                let (vid, pid) = parse_unique_id_vid_pid(&unique_id);
+                let mirrored = match device.position() {
+                    AVCaptureDevicePosition::Front => Some(true),
+                    AVCaptureDevicePosition::Back => Some(false),
+                    _ => None,
+                };
+
+                CameraDevice {
+                    name: device.localizedName().to_string(),
+                    manufacturer: (!manufacturer.is_empty()).then_some(manufacturer),
+                    device_path: Some(unique_id),
+                    driver: (!model_id.is_empty()).then_some(model_id),
+                    vid,
+                    pid,
+                    clsid: None,
+                    serial_number: None,
+                    orientation: None,
+                    mirrored,
+                    built_in: None,
+                    hardware_ids: Vec::new(),
+                    instance_id: None,
+                    enumeration_source: EnumerationSource::AvFoundation,
+                }
+            })
+            .inspect(log_device)
+            .collect()
+    }
+}
+
+// USB cameras report a uniqueID of the form `0x<location:8><vid:4><pid:4>`.
+fn parse_unique_id_vid_pid(unique_id: &str) -> (Option<String>, Option<String>) {
+    let Some(hex) = unique_id
+        .strip_prefix("0x")
+        .or_else(|| unique_id.strip_prefix("0X"))
+    else {
+        return (None, None);
+    };
+
+    if hex.len() != 16 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return (None, None);
+    }
+
+    let hex = hex.to_uppercase();
+    (Some(hex[8..12].to_string()), Some(hex[12..16].to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_vid_pid_from_usb_unique_ids() {
+        assert_eq!(
+            parse_unique_id_vid_pid("0x14100000046d0825"),
+            (Some("046D".to_string()), Some("0825".to_string()))
+        );
+        assert_eq!(
+            parse_unique_id_vid_pid("0X1410000005AC8514"),
+            (Some("05AC".to_string()), Some("8514".to_string()))
+        );
+    }
+
+    #[test]
+    fn other_unique_ids_have_no_vid_pid() {
+        for unique_id in [
+            "OBS Virtual Camera",
+            "EAB7A68F-EC2B-4487-AADF-D8A91C1CB782",
+            "0x14100000046d082",
+            "0x14100000046d08zz",
+        ] {
+            assert_eq!(
+                parse_unique_id_vid_pid(unique_id),
+                (None, None),
+                "{unique_id}"
+            );
+        }
+    }
+}
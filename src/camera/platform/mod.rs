@@ -0,0 +1,20 @@
+#[cfg(target_os = "linux")]
+pub(crate) mod linux;
+#[cfg(target_os = "macos")]
+pub(crate) mod macos;
+#[cfg(windows)]
+pub(crate) mod windows;
+#[cfg(windows)]
+pub(crate) mod windows_registry;
+#[cfg(windows)]
+pub(crate) mod windows_setupapi;
+
+#[cfg(any(windows, target_os = "linux", target_os = "macos"))]
+pub(crate) fn log_device(device: &super::device_enum::CameraDevice) {
+    log::debug!(
+        "{:?}: found {:?} at {}",
+        device.enumeration_source,
+        device.name,
+        device.device_path.as_deref().unwrap_or("<no path>")
+    );
+}
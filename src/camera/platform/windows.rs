@@ -0,0 +1,680 @@
+pub(crate) mod registry;
+
+use crate::camera::device_enum::{
+    parse_serial_number, parse_vid_pid, CameraDevice, EnumerationSource,
+};
+use crate::camera::diagnosis::instance_id_from_symbolic_link;
+use crate::camera::error::EnumerationError;
+use crate::camera::platform::log_device;
+use crate::camera::platform::windows_registry::{strip_indirect, DEVICE_CLASSES};
+use registry::read_registry_string;
+
+/// Keeps COM initialized on the current thread until dropped.
+pub(crate) struct ComApartment {
+    owned: bool,
+}
+
+impl ComApartment {
+    /// Joins the multithreaded apartment. A thread that is already in a
+    /// single-threaded apartment gets `RPC_E_CHANGED_MODE`; COM is usable
+    /// there, but the initialization belongs to the caller and is left alone.
+    pub(crate) fn enter() -> Result<Self, i32> {
+        use windows::Win32::Foundation::RPC_E_CHANGED_MODE;
+        use windows::Win32::System::Com::{CoInitializeEx, COINIT_MULTITHREADED};
+
+        let hresult = unsafe { CoInitializeEx(None, COINIT_MULTITHREADED) };
+        if hresult == RPC_E_CHANGED_MODE {
+            Ok(Self { owned: false })
+        } else if hresult.is_err() {
+            Err(hresult.0)
+        } else {
+            // S_FALSE (already initialized) still has to be balanced.
+            Ok(Self { owned: true })
+        }
+    }
+}
+
+impl Drop for ComApartment {
+    fn drop(&mut self) {
+        if self.owned {
+            unsafe { windows::Win32::System::Com::CoUninitialize() };
+        }
+    }
+}
+
+fn enter_com(backend: EnumerationSource) -> Result<ComApartment, EnumerationError> {
+    ComApartment::enter()
+        .map_err(|hresult| EnumerationError::ComInitFailed { backend, hresult })
+        .inspect_err(|error| {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(
+                ?backend,
+                hresult = error.hresult(),
+                "COM initialization failed"
+            );
+            log_failure(error);
+        })
+}
+
+fn log_failure(error: &EnumerationError) {
+    log::debug!("{error}");
+}
+
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", err(Display))
+)]
+pub(crate) fn enumerate_media_foundation_devices() -> Result<Vec<CameraDevice>, EnumerationError> {
+    let _com = enter_com(EnumerationSource::MediaFoundation)?;
+    media_foundation_devices().inspect_err(log_failure)
+}
+
+// Expects COM to be initialized on the calling thread.
+fn media_foundation_devices() -> Result<Vec<CameraDevice>, EnumerationError> {
+    use windows::Win32::Media::MediaFoundation::{
+        IMFActivate, MFCreateAttributes, MFEnumDeviceSources, MFShutdown, MFStartup,
+        MF_DEVSOURCE_ATTRIBUTE_FRIENDLY_NAME, MF_DEVSOURCE_ATTRIBUTE_SOURCE_TYPE,
+        MF_DEVSOURCE_ATTRIBUTE_SOURCE_TYPE_VIDCAP_GUID,
+        MF_DEVSOURCE_ATTRIBUTE_SOURCE_TYPE_VIDCAP_SYMBOLIC_LINK, MF_VERSION,
+    };
+    use windows::Win32::System::Com::CoTaskMemFree;
+
+    let mut devices = Vec::new();
+
+    unsafe {
+        if let Err(error) = MFStartup(MF_VERSION, 0) {
+            return Err(EnumerationError::MediaFoundationStartupFailed {
+                hresult: error.code().0,
+            });
+        }
+
+        let mut attributes = None;
+        if let Err(error) = MFCreateAttributes(&mut attributes, 1) {
+            MFShutdown().ok();
+            return Err(EnumerationError::AttributeCreationFailed {
+                hresult: error.code().0,
+            });
+        }
+        let attributes = attributes.unwrap();
+        if let Err(error) = attributes.SetGUID(
+            &MF_DEVSOURCE_ATTRIBUTE_SOURCE_TYPE,
+            &MF_DEVSOURCE_ATTRIBUTE_SOURCE_TYPE_VIDCAP_GUID,
+        ) {
+            MFShutdown().ok();
+            return Err(EnumerationError::AttributeCreationFailed {
+                hresult: error.code().0,
+            });
+        }
+
+        let mut activates: *mut Option<IMFActivate> = std::ptr::null_mut();
+        let mut count = 0;
+        if let Err(error) = MFEnumDeviceSources(&attributes, &mut activates, &mut count) {
+            MFShutdown().ok();
+            return Err(EnumerationError::DeviceEnumFailed {
+                backend: EnumerationSource::MediaFoundation,
+                hresult: error.code().0,
+            });
+        }
+        if !activates.is_null() {
+            let entries = std::slice::from_raw_parts_mut(activates, count as usize);
+            for entry in entries.iter_mut() {
+                if let Some(activate) = entry.take() {
+                    let name =
+                        get_activate_string(&activate, &MF_DEVSOURCE_ATTRIBUTE_FRIENDLY_NAME)
+                            .unwrap_or_else(|| "Unknown Camera".to_string());
+                    let device_path = get_activate_string(
+                        &activate,
+                        &MF_DEVSOURCE_ATTRIBUTE_SOURCE_TYPE_VIDCAP_SYMBOLIC_LINK,
+                    );
+                    let (vid, pid) = parse_vid_pid(device_path.as_deref());
+                    let serial_number = parse_serial_number(device_path.as_deref());
+                    let (orientation, mirrored, built_in) =
+                        read_panel_location(device_path.as_deref());
+                    let (manufacturer, driver) = read_enum_key_info(device_path.as_deref());
+                    let instance_id = device_path
+                        .as_deref()
+                        .and_then(instance_id_from_symbolic_link);
+                    let clsid = read_interface_clsid(device_path.as_deref());
+
+                    let device = CameraDevice {
+                        name,
+                        manufacturer,
+                        device_path,
+                        driver,
+                        vid,
+                        pid,
+                        clsid,
+                        serial_number,
+                        orientation,
+                        mirrored,
+                        built_in,
+                        hardware_ids: Vec::new(),
+                        instance_id,
+                        enumeration_source: EnumerationSource::MediaFoundation,
+                    };
+                    let device = with_vendor_manufacturer(device);
+                    log_device(&device);
+                    devices.push(device);
+                }
+            }
+            CoTaskMemFree(Some(activates as _));
+        }
+
+        MFShutdown().ok();
+    }
+
+    Ok(devices)
+}
+
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", err(Display))
+)]
+pub(crate) fn enumerate_directshow_devices() -> Result<Vec<CameraDevice>, EnumerationError> {
+    let _com = enter_com(EnumerationSource::DirectShow)?;
+    directshow_devices().inspect_err(log_failure)
+}
+
+// Expects COM to be initialized on the calling thread.
+fn directshow_devices() -> Result<Vec<CameraDevice>, EnumerationError> {
+    use windows::Win32::Media::DirectShow::ICreateDevEnum;
+    use windows::Win32::Media::MediaFoundation::{
+        CLSID_SystemDeviceEnum, CLSID_VideoInputDeviceCategory,
+    };
+    use windows::Win32::System::Com::StructuredStorage::IPropertyBag;
+    use windows::Win32::System::Com::{
+        CoCreateInstance, IEnumMoniker, IMoniker, CLSCTX_INPROC_SERVER,
+    };
+
+    let mut devices = Vec::new();
+
+    unsafe {
+        let enumerator: ICreateDevEnum =
+            match CoCreateInstance(&CLSID_SystemDeviceEnum, None, CLSCTX_INPROC_SERVER) {
+                Ok(enumerator) => enumerator,
+                Err(error) => {
+                    return Err(EnumerationError::NoEnumeratorCreated {
+                        hresult: error.code().0,
+                    });
+                }
+            };
+
+        let mut class_enum: Option<IEnumMoniker> = None;
+        if let Err(error) =
+            enumerator.CreateClassEnumerator(&CLSID_VideoInputDeviceCategory, &mut class_enum, 0)
+        {
+            return Err(EnumerationError::DeviceEnumFailed {
+                backend: EnumerationSource::DirectShow,
+                hresult: error.code().0,
+            });
+        }
+
+        // An empty category yields S_FALSE and no enumerator.
+        let class_enum = match class_enum {
+            Some(class_enum) => class_enum,
+            None => return Ok(devices),
+        };
+
+        loop {
+            let mut monikers: [Option<IMoniker>; 1] = [None];
+            let mut fetched = 0;
+            if class_enum.Next(&mut monikers, Some(&mut fetched)).is_err() || fetched == 0 {
+                break;
+            }
+
+            let Some(moniker) = monikers[0].take() else {
+                continue;
+            };
+
+            let property_bag: IPropertyBag = match moniker.BindToStorage(None, None) {
+                Ok(property_bag) => property_bag,
+                Err(error) => {
+                    log::trace!(
+                        "DirectShow: skipping a device whose property bag failed to bind \
+                         (HRESULT {:#010x})",
+                        error.code().0
+                    );
+                    continue;
+                }
+            };
+
+            let name = read_property_bag_string(&property_bag, "FriendlyName")
+                .unwrap_or_else(|| "Unknown Camera".to_string());
+            let manufacturer = read_property_bag_string(&property_bag, "Manufacturer");
+            let device_path = read_property_bag_string(&property_bag, "DevicePath");
+            let driver = read_property_bag_string(&property_bag, "Driver");
+            let clsid = read_property_bag_string(&property_bag, "CLSID")
+                .or_else(|| read_software_filter_clsid(&moniker));
+            let (vid, pid) = parse_vid_pid(device_path.as_deref());
+            let serial_number = parse_serial_number(device_path.as_deref());
+            let (orientation, mirrored, built_in) = read_panel_location(device_path.as_deref());
+            let instance_id = device_path
+                .as_deref()
+                .and_then(instance_id_from_symbolic_link);
+
+            let device = CameraDevice {
+                name,
+                manufacturer,
+                device_path,
+                driver,
+                vid,
+                pid,
+                clsid,
+                serial_number,
+                orientation,
+                mirrored,
+                built_in,
+                hardware_ids: Vec::new(),
+                instance_id,
+                enumeration_source: EnumerationSource::DirectShow,
+            };
+            let device = with_vendor_manufacturer(device);
+            log_device(&device);
+            devices.push(device);
+        }
+    }
+
+    Ok(devices)
+}
+
+// Generic UVC devices often report no manufacturer; the VID still names it.
+fn with_vendor_manufacturer(mut device: CameraDevice) -> CameraDevice {
+    if device.manufacturer.is_none() {
+        device.manufacturer = device.vendor_name().map(String::from);
+    }
+    device
+}
+
+// Software source filters are registered as `@device:sw:{category}\{clsid}`;
+// hardware devices (`@device:pnp:...`) all go through KsProxy and carry no
+// filter CLSID of their own.
+fn read_software_filter_clsid(moniker: &windows::Win32::System::Com::IMoniker) -> Option<String> {
+    use windows::Win32::System::Com::{CoTaskMemFree, CreateBindCtx};
+
+    unsafe {
+        let bind_context = CreateBindCtx(0).ok()?;
+        let display_name = moniker.GetDisplayName(&bind_context, None).ok()?;
+        if display_name.is_null() {
+            return None;
+        }
+        let name = display_name.to_string().ok();
+        CoTaskMemFree(Some(display_name.0 as _));
+
+        let name = name?;
+        let filter = name.strip_prefix("@device:sw:")?.rsplit('\\').next()?;
+        (filter.starts_with('{') && filter.ends_with('}')).then(|| filter.to_string())
+    }
+}
+
+fn get_activate_string(
+    activate: &windows::Win32::Media::MediaFoundation::IMFActivate,
+    key: &windows::core::GUID,
+) -> Option<String> {
+    use windows::core::PWSTR;
+
+    unsafe {
+        let mut string_ptr = PWSTR::null();
+        let mut length = 0;
+        if activate
+            .GetAllocatedString(key, &mut string_ptr, &mut length)
+            .is_err()
+        {
+            return None;
+        }
+        if string_ptr.is_null() {
+            return None;
+        }
+        let slice = std::slice::from_raw_parts(string_ptr.0, length as usize);
+        let string = String::from_utf16_lossy(slice);
+        windows::Win32::System::Com::CoTaskMemFree(Some(string_ptr.0 as _));
+        normalize_string(string)
+    }
+}
+
+pub(crate) fn read_property_bag_string(
+    property_bag: &windows::Win32::System::Com::StructuredStorage::IPropertyBag,
+    name: &str,
+) -> Option<String> {
+    use windows::core::{BSTR, VARIANT};
+
+    unsafe {
+        // `VARIANT` clears itself on drop and every conversion below copies,
+        // so nothing is freed twice.
+        let mut variant = VARIANT::default();
+        if property_bag
+            .Read(&BSTR::from(name), &mut variant, None)
+            .is_err()
+        {
+            return None;
+        }
+        variant_to_string(&variant).and_then(normalize_string)
+    }
+}
+
+// Some drivers count a terminating NUL, or padding, as part of the value.
+// Whatever follows the first NUL is not part of the string for C callers
+// either. Values that end up empty are treated as missing.
+fn normalize_string(value: String) -> Option<String> {
+    let value = value.split('\0').next().unwrap_or_default().trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+// Some drivers store `DevicePath` as a plain wide or ANSI string pointer
+// rather than a BSTR, and numeric properties come back as integers. Those
+// pointer types are not valid in a VARIANT, so `VariantClear` leaves their
+// buffers alone; freeing them here could use the wrong allocator.
+unsafe fn variant_to_string(variant: &windows::core::VARIANT) -> Option<String> {
+    use windows::core::{BSTR, PCSTR, PCWSTR};
+    use windows::Win32::System::Variant::{VT_BSTR, VT_I4, VT_LPSTR, VT_LPWSTR, VT_UI4};
+
+    let raw = &variant.as_raw().Anonymous.Anonymous;
+    match raw.vt {
+        vt if vt == VT_BSTR.0 => BSTR::try_from(variant).ok().map(|value| value.to_string()),
+        vt if vt == VT_LPWSTR.0 => {
+            let pointer = raw.Anonymous.byref as *const u16;
+            (!pointer.is_null())
+                .then(|| PCWSTR(pointer).to_string().ok())
+                .flatten()
+        }
+        vt if vt == VT_LPSTR.0 => {
+            let pointer = raw.Anonymous.byref as *const u8;
+            (!pointer.is_null())
+                .then(|| PCSTR(pointer).to_string().ok())
+                .flatten()
+        }
+        vt if vt == VT_I4.0 => Some(raw.Anonymous.lVal.to_string()),
+        vt if vt == VT_UI4.0 => Some(raw.Anonymous.ulVal.to_string()),
+        _ => None,
+    }
+}
+
+// Media Foundation has no manufacturer or driver attribute, so they are
+// read from the device's `Mfg` and `Service` values under the Enum key.
+fn read_enum_key_info(device_path: Option<&str>) -> (Option<String>, Option<String>) {
+    use windows::Win32::System::Registry::HKEY_LOCAL_MACHINE;
+
+    let Some(instance_id) = device_path.and_then(instance_id_from_symbolic_link) else {
+        return (None, None);
+    };
+    let key = format!("SYSTEM\\CurrentControlSet\\Enum\\{instance_id}");
+    let read = |value: &str| {
+        read_registry_string(HKEY_LOCAL_MACHINE, &key, value).filter(|data| !data.is_empty())
+    };
+
+    let manufacturer = read("Mfg").map(|mfg| strip_indirect(&mfg));
+    (manufacturer.filter(|mfg| !mfg.is_empty()), read("Service"))
+}
+
+// The `CLSID` value DirectShow reads through its property bag lives in the
+// interface's `Device Parameters` key, under the category GUID that ends the
+// symbolic link. Any reference string after the GUID names the `#` subkey.
+fn read_interface_clsid(device_path: Option<&str>) -> Option<String> {
+    use windows::Win32::System::Registry::HKEY_LOCAL_MACHINE;
+
+    let path = device_path?.strip_prefix("\\\\?\\")?;
+    let (interface, reference) = path.split_once('\\').unwrap_or((path, ""));
+    let (_, category) = interface.rsplit_once('#')?;
+    if !category.starts_with('{') {
+        return None;
+    }
+
+    let key =
+        format!("{DEVICE_CLASSES}\\{category}\\##?#{interface}\\#{reference}\\Device Parameters");
+    read_registry_string(HKEY_LOCAL_MACHINE, &key, "CLSID").and_then(normalize_string)
+}
+
+// Integrated cameras carry an ACPI _PLD buffer describing the panel they sit on
+// and their rotation; this is what WinRT exposes as EnclosureLocation. USB
+// ports describe themselves the same way with the UserVisible bit set, and
+// what that says about a plugged-in camera is only where the port is. Returns
+// the rotation, mirroring and whether the device is built in.
+fn read_panel_location(device_path: Option<&str>) -> (Option<i32>, Option<bool>, Option<bool>) {
+    use windows::core::HSTRING;
+    use windows::Win32::Devices::DeviceAndDriverInstallation::{
+        CM_Get_DevNode_PropertyW, CM_Locate_DevNodeW, CM_LOCATE_DEVNODE_NORMAL, CR_SUCCESS,
+    };
+    use windows::Win32::Devices::Properties::{DEVPKEY_Device_PhysicalDeviceLocation, DEVPROPTYPE};
+
+    let Some(instance_id) = device_path.and_then(instance_id_from_symbolic_link) else {
+        return (None, None, None);
+    };
+
+    let mut buffer = [0u8; 32];
+    unsafe {
+        let mut devinst = 0;
+        if CM_Locate_DevNodeW(
+            &mut devinst,
+            &HSTRING::from(instance_id),
+            CM_LOCATE_DEVNODE_NORMAL,
+        ) != CR_SUCCESS
+        {
+            return (None, None, None);
+        }
+
+        let mut property_type = DEVPROPTYPE::default();
+        let mut size = buffer.len() as u32;
+        if CM_Get_DevNode_PropertyW(
+            devinst,
+            &DEVPKEY_Device_PhysicalDeviceLocation,
+            &mut property_type,
+            Some(buffer.as_mut_ptr()),
+            &mut size,
+            0,
+        ) != CR_SUCCESS
+            || size < 16
+        {
+            return (None, None, None);
+        }
+    }
+
+    parse_panel_location(&buffer)
+}
+
+fn parse_panel_location(buffer: &[u8; 32]) -> (Option<i32>, Option<bool>, Option<bool>) {
+    const PLD_PANEL_FRONT: u8 = 4;
+    const PLD_PANEL_BACK: u8 = 5;
+
+    // UserVisible is bit 64, panel bits 67..=69 and rotation bits 115..=118
+    // in 45 degree steps.
+    if buffer[8] & 0x01 != 0 {
+        return (None, None, Some(false));
+    }
+    let panel = (buffer[8] >> 3) & 0x07;
+    let rotation = i32::from((buffer[14] >> 3) & 0x0f) * 45;
+    let mirrored = match panel {
+        PLD_PANEL_FRONT => Some(true),
+        PLD_PANEL_BACK => Some(false),
+        _ => None,
+    };
+
+    (Some(rotation), mirrored, Some(true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A revision 2 _PLD buffer with the given UserVisible bit, panel and
+    // rotation in 45 degree steps.
+    fn pld(user_visible: bool, panel: u8, rotation: u8) -> [u8; 32] {
+        let mut buffer = [0u8; 32];
+        buffer[0] = 0x82;
+        buffer[8] = u8::from(user_visible) | (panel << 3);
+        buffer[14] = rotation << 3;
+        buffer
+    }
+
+    #[test]
+    fn built_in_cameras_report_panel_and_rotation() {
+        assert_eq!(
+            parse_panel_location(&pld(false, 4, 0)),
+            (Some(0), Some(true), Some(true))
+        );
+        assert_eq!(
+            parse_panel_location(&pld(false, 5, 2)),
+            (Some(90), Some(false), Some(true))
+        );
+        // Top, bottom, left and right say nothing about facing the user.
+        assert_eq!(
+            parse_panel_location(&pld(false, 0, 4)),
+            (Some(180), None, Some(true))
+        );
+    }
+
+    #[test]
+    fn user_visible_ports_only_report_not_built_in() {
+        assert_eq!(
+            parse_panel_location(&pld(true, 4, 2)),
+            (None, None, Some(false))
+        );
+    }
+
+    #[test]
+    fn devices_without_a_path_have_no_panel_location() {
+        assert_eq!(read_panel_location(None), (None, None, None));
+    }
+
+    fn on_sta_thread<T: Send>(work: impl FnOnce() -> T + Send) -> T {
+        use windows::Win32::System::Com::{
+            CoInitializeEx, CoUninitialize, COINIT_APARTMENTTHREADED,
+        };
+
+        std::thread::scope(|scope| {
+            scope
+                .spawn(|| {
+                    unsafe { CoInitializeEx(None, COINIT_APARTMENTTHREADED) }
+                        .ok()
+                        .expect("a new thread can enter an STA");
+                    let result = work();
+                    unsafe { CoUninitialize() };
+                    result
+                })
+                .join()
+                .unwrap()
+        })
+    }
+
+    #[test]
+    fn enumerates_on_a_caller_sta_thread() {
+        use windows::Win32::Foundation::S_FALSE;
+        use windows::Win32::System::Com::{
+            CoInitializeEx, CoUninitialize, COINIT_APARTMENTTHREADED,
+        };
+
+        let still_sta = on_sta_thread(|| {
+            let media_foundation = enumerate_media_foundation_devices();
+            let directshow = enumerate_directshow_devices();
+            assert!(!matches!(
+                media_foundation,
+                Err(EnumerationError::ComInitFailed { .. })
+            ));
+            assert!(!matches!(
+                directshow,
+                Err(EnumerationError::ComInitFailed { .. })
+            ));
+
+            // The caller's initialization is left in place.
+            let hresult = unsafe { CoInitializeEx(None, COINIT_APARTMENTTHREADED) };
+            if hresult.is_ok() {
+                unsafe { CoUninitialize() };
+            }
+            hresult == S_FALSE
+        });
+
+        assert!(still_sta);
+    }
+
+    #[test]
+    fn com_apartment_owns_only_its_own_initialization() {
+        let (first, nested) = std::thread::scope(|scope| {
+            scope
+                .spawn(|| {
+                    let first = ComApartment::enter().unwrap();
+                    // S_FALSE: already in the apartment, but still counted.
+                    let nested = ComApartment::enter().unwrap();
+                    (first.owned, nested.owned)
+                })
+                .join()
+                .unwrap()
+        });
+        assert!(first && nested);
+
+        // RPC_E_CHANGED_MODE: the caller's STA is not ours to leave.
+        let owned = on_sta_thread(|| ComApartment::enter().unwrap().owned);
+        assert!(!owned);
+    }
+
+    // A VARIANT holding a raw string pointer; such types are not valid
+    // VARIANTs, so it is never cleared.
+    fn pointer_variant(
+        vt: windows::Win32::System::Variant::VARENUM,
+        pointer: *const std::ffi::c_void,
+    ) -> std::mem::ManuallyDrop<windows::core::VARIANT> {
+        let mut raw = *windows::core::VARIANT::default().as_raw();
+        raw.Anonymous.Anonymous.vt = vt.0;
+        raw.Anonymous.Anonymous.Anonymous.byref = pointer as *mut _;
+        std::mem::ManuallyDrop::new(unsafe { windows::core::VARIANT::from_raw(raw) })
+    }
+
+    #[test]
+    fn variants_convert_to_strings() {
+        use windows::core::{BSTR, VARIANT};
+        use windows::Win32::System::Variant::{VT_LPSTR, VT_LPWSTR};
+
+        let wide = "\\\\?\\usb#vid_046d&pid_0825"
+            .encode_utf16()
+            .chain([0])
+            .collect::<Vec<_>>();
+        let narrow = b"USB Camera\0";
+
+        unsafe {
+            assert_eq!(
+                variant_to_string(&VARIANT::from(BSTR::from("Integrated Camera"))).as_deref(),
+                Some("Integrated Camera")
+            );
+            assert_eq!(
+                variant_to_string(&pointer_variant(VT_LPWSTR, wide.as_ptr().cast())).as_deref(),
+                Some("\\\\?\\usb#vid_046d&pid_0825")
+            );
+            assert_eq!(
+                variant_to_string(&pointer_variant(VT_LPSTR, narrow.as_ptr().cast())).as_deref(),
+                Some("USB Camera")
+            );
+            assert_eq!(
+                variant_to_string(&pointer_variant(VT_LPWSTR, std::ptr::null())),
+                None
+            );
+            assert_eq!(
+                variant_to_string(&VARIANT::from(-2i32)).as_deref(),
+                Some("-2")
+            );
+            assert_eq!(
+                variant_to_string(&VARIANT::from(4_000_000_000u32)).as_deref(),
+                Some("4000000000")
+            );
+            assert_eq!(variant_to_string(&VARIANT::from(true)), None);
+            assert_eq!(variant_to_string(&VARIANT::default()), None);
+        }
+    }
+
+    #[test]
+    fn strings_stop_at_the_first_nul_and_are_trimmed() {
+        let cases = [
+            ("Integrated Camera\0", Some("Integrated Camera")),
+            ("Integrated Camera\0\0\0", Some("Integrated Camera")),
+            ("  USB Camera \0garbage\0", Some("USB Camera")),
+            ("Front\0Camera", Some("Front")),
+            ("\0Camera", None),
+            ("   ", None),
+            ("", None),
+        ];
+
+        for (value, expected) in cases {
+            assert_eq!(
+                normalize_string(value.to_string()).as_deref(),
+                expected,
+                "{value:?}"
+            );
+        }
+    }
+}
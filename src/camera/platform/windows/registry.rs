@@ -0,0 +1,145 @@
+pub(crate) fn read_registry_string(
+    root: windows::Win32::System::Registry::HKEY,
+    subkey: &str,
+    value: &str,
+) -> Option<String> {
+    use windows::core::HSTRING;
+    use windows::Win32::Foundation::ERROR_SUCCESS;
+    use windows::Win32::System::Registry::{RegGetValueW, RRF_RT_REG_SZ};
+
+    unsafe {
+        let subkey = HSTRING::from(subkey);
+        let value = HSTRING::from(value);
+        let mut size = 0u32;
+        if RegGetValueW(
+            root,
+            &subkey,
+            &value,
+            RRF_RT_REG_SZ,
+            None,
+            None,
+            Some(&mut size),
+        ) != ERROR_SUCCESS
+        {
+            return None;
+        }
+
+        let mut buffer = vec![0u16; (size as usize).div_ceil(2)];
+        if RegGetValueW(
+            root,
+            &subkey,
+            &value,
+            RRF_RT_REG_SZ,
+            None,
+            Some(buffer.as_mut_ptr() as _),
+            Some(&mut size),
+        ) != ERROR_SUCCESS
+        {
+            return None;
+        }
+
+        let length = buffer.iter().position(|c| *c == 0).unwrap_or(buffer.len());
+        Some(String::from_utf16_lossy(&buffer[..length]))
+    }
+}
+
+pub(crate) fn read_registry_u32(
+    root: windows::Win32::System::Registry::HKEY,
+    subkey: &str,
+    value: &str,
+) -> Option<u32> {
+    use windows::core::HSTRING;
+    use windows::Win32::Foundation::ERROR_SUCCESS;
+    use windows::Win32::System::Registry::{RegGetValueW, RRF_RT_REG_DWORD};
+
+    unsafe {
+        let mut data = 0u32;
+        let mut size = std::mem::size_of::<u32>() as u32;
+        if RegGetValueW(
+            root,
+            &HSTRING::from(subkey),
+            &HSTRING::from(value),
+            RRF_RT_REG_DWORD,
+            None,
+            Some(&mut data as *mut u32 as _),
+            Some(&mut size),
+        ) != ERROR_SUCCESS
+        {
+            return None;
+        }
+        Some(data)
+    }
+}
+
+pub(crate) fn read_registry_u64(
+    root: windows::Win32::System::Registry::HKEY,
+    subkey: &str,
+    value: &str,
+) -> Option<u64> {
+    use windows::core::HSTRING;
+    use windows::Win32::Foundation::ERROR_SUCCESS;
+    use windows::Win32::System::Registry::{RegGetValueW, RRF_RT_REG_QWORD};
+
+    unsafe {
+        let mut data = 0u64;
+        let mut size = std::mem::size_of::<u64>() as u32;
+        if RegGetValueW(
+            root,
+            &HSTRING::from(subkey),
+            &HSTRING::from(value),
+            RRF_RT_REG_QWORD,
+            None,
+            Some(&mut data as *mut u64 as _),
+            Some(&mut size),
+        ) != ERROR_SUCCESS
+        {
+            return None;
+        }
+        Some(data)
+    }
+}
+
+pub(crate) fn enumerate_registry_subkeys(
+    root: windows::Win32::System::Registry::HKEY,
+    subkey: &str,
+) -> Vec<String> {
+    use windows::core::{HSTRING, PWSTR};
+    use windows::Win32::Foundation::ERROR_SUCCESS;
+    use windows::Win32::System::Registry::{
+        RegCloseKey, RegEnumKeyExW, RegOpenKeyExW, HKEY, KEY_READ,
+    };
+
+    let mut names = Vec::new();
+
+    unsafe {
+        let mut key = HKEY::default();
+        if RegOpenKeyExW(root, &HSTRING::from(subkey), 0, KEY_READ, &mut key) != ERROR_SUCCESS {
+            return names;
+        }
+
+        let mut index = 0;
+        loop {
+            let mut buffer = [0u16; 512];
+            let mut length = buffer.len() as u32;
+            if RegEnumKeyExW(
+                key,
+                index,
+                PWSTR(buffer.as_mut_ptr()),
+                &mut length,
+                None,
+                PWSTR::null(),
+                None,
+                None,
+            ) != ERROR_SUCCESS
+            {
+                break;
+            }
+            names.push(String::from_utf16_lossy(&buffer[..length as usize]));
+            index += 1;
+        }
+
+        RegCloseKey(key).ok().ok();
+    }
+
+    names
+}
@@ -0,0 +1,122 @@
+use std::collections::HashSet;
+
+use windows::Win32::System::Registry::HKEY_LOCAL_MACHINE;
+
+use crate::camera::device_enum::{
+    parse_serial_number, parse_vid_pid, CameraDevice, EnumerationSource,
+};
+use crate::camera::platform::log_device;
+use crate::camera::platform::windows::registry::{
+    enumerate_registry_subkeys, read_registry_string, read_registry_u32,
+};
+
+pub(crate) const DEVICE_CLASSES: &str = "SYSTEM\\CurrentControlSet\\Control\\DeviceClasses";
+const ENUM: &str = "SYSTEM\\CurrentControlSet\\Enum";
+// KSCATEGORY_CAPTURE also holds audio capture devices, so a device has to be
+// registered under KSCATEGORY_VIDEO as well to count as a camera.
+const KSCATEGORY_CAPTURE: &str = "{65e8773d-8f56-11d0-a3b9-00a0c9223196}";
+const KSCATEGORY_VIDEO: &str = "{6994ad05-93ef-11d0-a3cc-00a0c9223196}";
+
+/// Video capture interfaces registered under the kernel streaming device
+/// classes, which includes filter-driver cameras that neither Media
+/// Foundation nor DirectShow list.
+///
+/// Interfaces of devices that are not currently present are skipped.
+pub(crate) fn enumerate_registry_devices() -> Vec<CameraDevice> {
+    let capture_instances = linked_interfaces(KSCATEGORY_CAPTURE)
+        .into_iter()
+        .filter_map(|(_, _, instance)| instance)
+        .map(|instance| instance.to_lowercase())
+        .collect::<HashSet<_>>();
+
+    linked_interfaces(KSCATEGORY_VIDEO)
+        .into_iter()
+        .filter_map(|(interface_key, reference, instance)| {
+            let instance = instance?;
+            if !capture_instances.contains(&instance.to_lowercase()) {
+                return None;
+            }
+
+            let interface =
+                format!("{DEVICE_CLASSES}\\{KSCATEGORY_VIDEO}\\{interface_key}\\{reference}");
+            let device_path = read_registry_string(HKEY_LOCAL_MACHINE, &interface, "SymbolicLink")
+                .or_else(|| {
+                    let rest = interface_key.strip_prefix("##?#")?;
+                    Some(match &reference[1..] {
+                        "" => format!("\\\\?\\{rest}"),
+                        reference => format!("\\\\?\\{rest}\\{reference}"),
+                    })
+                });
+            let instance_key = format!("{ENUM}\\{instance}");
+            let name = read_registry_string(
+                HKEY_LOCAL_MACHINE,
+                &format!("{interface}\\Device Parameters"),
+                "FriendlyName",
+            )
+            .or_else(|| read_registry_string(HKEY_LOCAL_MACHINE, &instance_key, "FriendlyName"))
+            .or_else(|| read_registry_string(HKEY_LOCAL_MACHINE, &instance_key, "DeviceDesc"))
+            .map(|name| strip_indirect(&name))
+            .unwrap_or_else(|| "Unknown Camera".to_string());
+            let (vid, pid) = parse_vid_pid(device_path.as_deref());
+            let serial_number = parse_serial_number(device_path.as_deref());
+
+            let device = CameraDevice {
+                name,
+                manufacturer: read_registry_string(HKEY_LOCAL_MACHINE, &instance_key, "Mfg")
+                    .map(|mfg| strip_indirect(&mfg))
+                    .filter(|mfg| !mfg.is_empty()),
+                device_path,
+                driver: read_registry_string(HKEY_LOCAL_MACHINE, &instance_key, "Service"),
+                vid,
+                pid,
+                clsid: None,
+                serial_number,
+                orientation: None,
+                mirrored: None,
+                built_in: None,
+                hardware_ids: Vec::new(),
+                instance_id: Some(instance),
+                enumeration_source: EnumerationSource::Registry,
+            };
+            log_device(&device);
+            Some(device)
+        })
+        .collect()
+}
+
+// The interface subkeys of a device class that are linked, i.e. whose
+// device is present, with the linked reference subkey and their device
+// instance IDs. The reference subkey is `#` followed by the reference
+// string: plain `#` when there is none, `#GLOBAL` for most kernel
+// streaming interfaces.
+fn linked_interfaces(class: &str) -> Vec<(String, String, Option<String>)> {
+    let class_key = format!("{DEVICE_CLASSES}\\{class}");
+    enumerate_registry_subkeys(HKEY_LOCAL_MACHINE, &class_key)
+        .into_iter()
+        .filter_map(|interface_key| {
+            let interface = format!("{class_key}\\{interface_key}");
+            let reference = enumerate_registry_subkeys(HKEY_LOCAL_MACHINE, &interface)
+                .into_iter()
+                .filter(|subkey| subkey.starts_with('#'))
+                .find(|subkey| {
+                    read_registry_u32(
+                        HKEY_LOCAL_MACHINE,
+                        &format!("{interface}\\{subkey}\\Control"),
+                        "Linked",
+                    ) == Some(1)
+                })?;
+            let instance = read_registry_string(HKEY_LOCAL_MACHINE, &interface, "DeviceInstance");
+            Some((interface_key, reference, instance))
+        })
+        .collect()
+}
+
+// Device descriptions are often indirect strings such as
+// `@oem12.inf,%device.desc%;Integrated Camera`; the text after the last `;`
+// is the fallback that is shown when the resource cannot be loaded.
+pub(crate) fn strip_indirect(value: &str) -> String {
+    match value.strip_prefix('@') {
+        Some(indirect) => indirect.rsplit(';').next().unwrap_or_default().to_string(),
+        None => value.to_string(),
+    }
+}
@@ -0,0 +1,229 @@
+use windows::core::{GUID, PCWSTR};
+use windows::Win32::Devices::DeviceAndDriverInstallation::{
+    SetupDiDestroyDeviceInfoList, SetupDiEnumDeviceInterfaces, SetupDiGetClassDevsW,
+    SetupDiGetDeviceInstanceIdW, SetupDiGetDeviceInterfaceDetailW,
+    SetupDiGetDeviceRegistryPropertyW, DIGCF_DEVICEINTERFACE, DIGCF_PRESENT, HDEVINFO,
+    SETUP_DI_REGISTRY_PROPERTY, SPDRP_DEVICEDESC, SPDRP_DRIVER, SPDRP_FRIENDLYNAME,
+    SPDRP_HARDWAREID, SPDRP_MFG, SPDRP_SERVICE, SP_DEVICE_INTERFACE_DATA,
+    SP_DEVICE_INTERFACE_DETAIL_DATA_W, SP_DEVINFO_DATA,
+};
+use windows::Win32::Foundation::HWND;
+use windows::Win32::System::Registry::HKEY_LOCAL_MACHINE;
+
+use crate::camera::device_enum::{
+    parse_serial_number, parse_vid_pid, CameraDevice, EnumerationSource,
+};
+use crate::camera::platform::log_device;
+use crate::camera::platform::windows::registry::read_registry_string;
+use crate::camera::platform::windows_registry::strip_indirect;
+
+const DRIVER_CLASSES: &str = "SYSTEM\\CurrentControlSet\\Control\\Class";
+// KSCATEGORY_VIDEO_CAMERA, which every camera driver and frame server
+// source registers.
+const VIDEO_CAMERA_INTERFACE: GUID = GUID::from_u128(0xe5323777_f976_4f5b_9b55_b94699c46e44);
+
+/// Present camera interfaces as listed by SetupAPI, with the hardware IDs,
+/// instance ID, manufacturer and driver service of their devices.
+///
+/// The interface paths are the symbolic links Media Foundation reports, so
+/// deduplication pairs these records with the other backends' ones. The
+/// driver is the service name, or the INF file when the device has no
+/// service.
+pub(crate) fn enumerate_setupapi_devices() -> Vec<CameraDevice> {
+    let Ok(device_info) = (unsafe {
+        SetupDiGetClassDevsW(
+            Some(&VIDEO_CAMERA_INTERFACE),
+            PCWSTR::null(),
+            HWND::default(),
+            DIGCF_PRESENT | DIGCF_DEVICEINTERFACE,
+        )
+    }) else {
+        log::debug!("SetupAPI: no device information set for the camera interface class");
+        return Vec::new();
+    };
+
+    let mut devices = Vec::new();
+    for index in 0.. {
+        let mut interface = SP_DEVICE_INTERFACE_DATA {
+            cbSize: std::mem::size_of::<SP_DEVICE_INTERFACE_DATA>() as u32,
+            ..Default::default()
+        };
+        if unsafe {
+            SetupDiEnumDeviceInterfaces(
+                device_info,
+                None,
+                &VIDEO_CAMERA_INTERFACE,
+                index,
+                &mut interface,
+            )
+        }
+        .is_err()
+        {
+            break;
+        }
+
+        let Some((device_path, device_data)) = interface_detail(device_info, &interface) else {
+            continue;
+        };
+        let property = |property| read_string_property(device_info, &device_data, property);
+
+        let name = property(SPDRP_FRIENDLYNAME)
+            .or_else(|| property(SPDRP_DEVICEDESC))
+            .map(|name| strip_indirect(&name))
+            .unwrap_or_else(|| "Unknown Camera".to_string());
+        let driver = property(SPDRP_SERVICE).or_else(|| {
+            let driver_key = property(SPDRP_DRIVER)?;
+            read_registry_string(
+                HKEY_LOCAL_MACHINE,
+                &format!("{DRIVER_CLASSES}\\{driver_key}"),
+                "InfPath",
+            )
+        });
+        let (vid, pid) = parse_vid_pid(Some(&device_path));
+        let serial_number = parse_serial_number(Some(&device_path));
+
+        let device = CameraDevice {
+            name,
+            manufacturer: property(SPDRP_MFG)
+                .map(|mfg| strip_indirect(&mfg))
+                .filter(|mfg| !mfg.is_empty()),
+            device_path: Some(device_path),
+            driver,
+            vid,
+            pid,
+            clsid: None,
+            serial_number,
+            orientation: None,
+            mirrored: None,
+            built_in: None,
+            hardware_ids: read_property(device_info, &device_data, SPDRP_HARDWAREID)
+                .map(|data| split_multi_sz(&data))
+                .unwrap_or_default(),
+            instance_id: instance_id(device_info, &device_data),
+            enumeration_source: EnumerationSource::SetupApi,
+        };
+        log_device(&device);
+        devices.push(device);
+    }
+
+    unsafe { SetupDiDestroyDeviceInfoList(device_info) }.ok();
+    devices
+}
+
+// The interface path and the device that exposes it.
+fn interface_detail(
+    device_info: HDEVINFO,
+    interface: &SP_DEVICE_INTERFACE_DATA,
+) -> Option<(String, SP_DEVINFO_DATA)> {
+    let mut required = 0;
+    // Fails with ERROR_INSUFFICIENT_BUFFER, having set the size.
+    unsafe {
+        SetupDiGetDeviceInterfaceDetailW(device_info, interface, None, 0, Some(&mut required), None)
+    }
+    .ok();
+    if required == 0 {
+        return None;
+    }
+
+    // A u32 buffer keeps the detail structure aligned.
+    let mut buffer = vec![0u32; (required as usize).div_ceil(4)];
+    let detail = buffer
+        .as_mut_ptr()
+        .cast::<SP_DEVICE_INTERFACE_DETAIL_DATA_W>();
+    let mut device_data = SP_DEVINFO_DATA {
+        cbSize: std::mem::size_of::<SP_DEVINFO_DATA>() as u32,
+        ..Default::default()
+    };
+    unsafe {
+        (*detail).cbSize = std::mem::size_of::<SP_DEVICE_INTERFACE_DETAIL_DATA_W>() as u32;
+        SetupDiGetDeviceInterfaceDetailW(
+            device_info,
+            interface,
+            Some(detail),
+            required,
+            None,
+            Some(&mut device_data),
+        )
+        .ok()?;
+        PCWSTR((*detail).DevicePath.as_ptr())
+            .to_string()
+            .ok()
+            .map(|path| (path, device_data))
+    }
+}
+
+fn instance_id(device_info: HDEVINFO, device_data: &SP_DEVINFO_DATA) -> Option<String> {
+    let mut required = 0;
+    unsafe { SetupDiGetDeviceInstanceIdW(device_info, device_data, None, Some(&mut required)) }
+        .ok();
+    if required == 0 {
+        return None;
+    }
+
+    let mut buffer = vec![0u16; required as usize];
+    unsafe { SetupDiGetDeviceInstanceIdW(device_info, device_data, Some(&mut buffer), None) }
+        .ok()?;
+    let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+    Some(String::from_utf16_lossy(&buffer[..len]))
+}
+
+// The raw data of a device registry property as UTF-16 code units.
+fn read_property(
+    device_info: HDEVINFO,
+    device_data: &SP_DEVINFO_DATA,
+    property: SETUP_DI_REGISTRY_PROPERTY,
+) -> Option<Vec<u16>> {
+    let mut required = 0;
+    unsafe {
+        SetupDiGetDeviceRegistryPropertyW(
+            device_info,
+            device_data,
+            property,
+            None,
+            None,
+            Some(&mut required),
+        )
+    }
+    .ok();
+    if required == 0 {
+        return None;
+    }
+
+    let mut buffer = vec![0u8; required as usize];
+    unsafe {
+        SetupDiGetDeviceRegistryPropertyW(
+            device_info,
+            device_data,
+            property,
+            None,
+            Some(&mut buffer),
+            None,
+        )
+    }
+    .ok()?;
+    Some(
+        buffer
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect(),
+    )
+}
+
+fn read_string_property(
+    device_info: HDEVINFO,
+    device_data: &SP_DEVINFO_DATA,
+    property: SETUP_DI_REGISTRY_PROPERTY,
+) -> Option<String> {
+    split_multi_sz(&read_property(device_info, device_data, property)?)
+        .into_iter()
+        .next()
+}
+
+// REG_MULTI_SZ data: strings separated by NULs and ended by an empty one.
+// A plain REG_SZ comes out as a single string.
+fn split_multi_sz(data: &[u16]) -> Vec<String> {
+    data.split(|&c| c == 0)
+        .take_while(|string| !string.is_empty())
+        .map(String::from_utf16_lossy)
+        .collect()
+}
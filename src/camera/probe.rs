@@ -0,0 +1,104 @@
+use std::time::Duration;
+
+use super::device_enum::CameraDevice;
+
+// Stream ticks and format changes come back without a sample; a device
+// that keeps sending only those is not delivering video.
+#[cfg(windows)]
+const MAX_READS: usize = 30;
+
+/// Whether `device` delivers a video frame within `timeout`.
+///
+/// Opens the device through a Media Foundation source reader and waits for
+/// one sample, which briefly turns the camera on. Entries left behind by a
+/// crashed driver enumerate but never get this far. The read runs on its
+/// own thread, so a device wedged in a driver call cannot hold the caller
+/// past `timeout`; that thread stays blocked until the driver returns and
+/// then cleans up after itself.
+///
+/// Devices without a path, and every device outside Windows, are not live.
+pub fn probe_live(device: &CameraDevice, timeout: Duration) -> bool {
+    #[cfg(windows)]
+    {
+        use std::sync::mpsc;
+
+        let Some(symbolic_link) = device.device_path.clone() else {
+            return false;
+        };
+        let (sender, receiver) = mpsc::channel();
+        let spawned = std::thread::Builder::new()
+            .name("camera-probe".to_string())
+            .spawn(move || {
+                // The receiver is gone when the probe timed out.
+                sender.send(read_one_sample(&symbolic_link)).ok();
+            });
+        if spawned.is_err() {
+            return false;
+        }
+
+        receiver.recv_timeout(timeout).unwrap_or_else(|_| {
+            log::debug!("{:?} delivered no frame within {timeout:?}", device.name);
+            false
+        })
+    }
+
+    #[cfg(not(windows))]
+    {
+        let _ = (device, timeout);
+        false
+    }
+}
+
+#[cfg(windows)]
+fn read_one_sample(symbolic_link: &str) -> bool {
+    use windows::Win32::Media::MediaFoundation::{
+        MFCreateSourceReaderFromMediaSource, MFShutdown, MFStartup, MF_SOURCE_READERF_ENDOFSTREAM,
+        MF_SOURCE_READERF_ERROR, MF_SOURCE_READER_FIRST_VIDEO_STREAM, MF_VERSION,
+    };
+
+    use super::capabilities::create_device_source;
+    use super::platform::windows::ComApartment;
+
+    let Ok(_com) = ComApartment::enter() else {
+        return false;
+    };
+
+    unsafe {
+        if MFStartup(MF_VERSION, 0).is_err() {
+            return false;
+        }
+
+        let delivered = create_device_source(symbolic_link).and_then(|source| {
+            let delivered = (|| {
+                let reader = MFCreateSourceReaderFromMediaSource(&source, None)?;
+                let stream = MF_SOURCE_READER_FIRST_VIDEO_STREAM.0 as u32;
+                let stop = (MF_SOURCE_READERF_ENDOFSTREAM.0 | MF_SOURCE_READERF_ERROR.0) as u32;
+                for _ in 0..MAX_READS {
+                    let mut flags = 0;
+                    let mut sample = None;
+                    reader.ReadSample(
+                        stream,
+                        0,
+                        None,
+                        Some(&mut flags),
+                        None,
+                        Some(&mut sample),
+                    )?;
+                    if sample.is_some() {
+                        return Ok(true);
+                    }
+                    if flags & stop != 0 {
+                        break;
+                    }
+                }
+                Ok(false)
+            })();
+            source.Shutdown().ok();
+            delivered
+        });
+
+        MFShutdown().ok();
+
+        delivered.unwrap_or(false)
+    }
+}
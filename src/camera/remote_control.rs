@@ -0,0 +1,276 @@
+use super::device_enum::{enumerate_devices, is_virtual_camera, CameraDevice};
+use super::error::EnumerationError;
+
+const REMOTE_CONTROL_CAMERA_KEYWORDS: [&str; 8] = [
+    "teamviewer",
+    "anydesk",
+    "splashtop",
+    "rustdesk",
+    "screenconnect",
+    "logmein",
+    "rdcamera",
+    "remote desktop camera",
+];
+
+const REMOTE_CONTROL_PROCESSES: [&str; 12] = [
+    "teamviewer",
+    "teamviewer_service",
+    "anydesk",
+    "rustdesk",
+    "srserver",
+    "srservice",
+    "remoting_host",
+    "screenconnect.clientservice",
+    "logmein",
+    "winvnc",
+    "tvnserver",
+    "vncserver",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum VirtualCameraKind {
+    Software,
+    RemoteControl,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SessionContext {
+    pub remote_desktop_session: bool,
+    pub remote_control_processes: Vec<String>,
+}
+
+impl SessionContext {
+    pub fn remote_control_active(&self) -> bool {
+        self.remote_desktop_session || !self.remote_control_processes.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RemoteControlReport {
+    pub remote_control_cameras: Vec<CameraDevice>,
+    pub session: SessionContext,
+}
+
+impl RemoteControlReport {
+    pub fn remote_control_active(&self) -> bool {
+        !self.remote_control_cameras.is_empty() || self.session.remote_control_active()
+    }
+}
+
+pub fn virtual_camera_kind(device: &CameraDevice) -> Option<VirtualCameraKind> {
+    if is_remote_control_camera(device) {
+        Some(VirtualCameraKind::RemoteControl)
+    } else if is_virtual_camera(device) {
+        Some(VirtualCameraKind::Software)
+    } else {
+        None
+    }
+}
+
+pub fn remote_control_report() -> RemoteControlReport {
+    let devices = enumerate_devices().unwrap_or_else(EnumerationError::into_partial_devices);
+    remote_control_report_from(&devices, detect_session_context())
+}
+
+pub fn remote_control_report_from(
+    devices: &[CameraDevice],
+    session: SessionContext,
+) -> RemoteControlReport {
+    RemoteControlReport {
+        remote_control_cameras: devices
+            .iter()
+            .filter(|device| is_remote_control_camera(device))
+            .cloned()
+            .collect(),
+        session,
+    }
+}
+
+pub fn detect_session_context() -> SessionContext {
+    SessionContext {
+        remote_desktop_session: is_remote_desktop_session(),
+        remote_control_processes: match_remote_control_processes(&running_process_names()),
+    }
+}
+
+pub fn match_remote_control_processes(process_names: &[String]) -> Vec<String> {
+    process_names
+        .iter()
+        .filter(|name| {
+            let lower = name.to_lowercase();
+            let stem = lower.strip_suffix(".exe").unwrap_or(&lower);
+            REMOTE_CONTROL_PROCESSES.contains(&stem)
+        })
+        .cloned()
+        .collect()
+}
+
+pub(crate) fn is_remote_control_camera(device: &CameraDevice) -> bool {
+    [
+        Some(&device.name),
+        device.manufacturer.as_ref(),
+        device.driver.as_ref(),
+        device.device_path.as_ref(),
+    ]
+    .into_iter()
+    .flatten()
+    .map(|value| value.to_lowercase())
+    .any(|value| {
+        REMOTE_CONTROL_CAMERA_KEYWORDS
+            .iter()
+            .any(|keyword| value.contains(keyword))
+    })
+}
+
+fn is_remote_desktop_session() -> bool {
+    #[cfg(windows)]
+    {
+        use windows::Win32::UI::WindowsAndMessaging::{GetSystemMetrics, SM_REMOTESESSION};
+
+        unsafe { GetSystemMetrics(SM_REMOTESESSION) != 0 }
+    }
+
+    #[cfg(not(windows))]
+    {
+        false
+    }
+}
+
+fn running_process_names() -> Vec<String> {
+    #[cfg(windows)]
+    {
+        running_windows_process_names()
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let Ok(entries) = std::fs::read_dir("/proc") else {
+            return Vec::new();
+        };
+        entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| {
+                entry
+                    .file_name()
+                    .to_string_lossy()
+                    .bytes()
+                    .all(|b| b.is_ascii_digit())
+            })
+            .filter_map(|entry| std::fs::read_to_string(entry.path().join("comm")).ok())
+            .map(|comm| comm.trim().to_string())
+            .collect()
+    }
+
+    #[cfg(not(any(windows, target_os = "linux")))]
+    {
+        Vec::new()
+    }
+}
+
+#[cfg(windows)]
+fn running_windows_process_names() -> Vec<String> {
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W,
+        TH32CS_SNAPPROCESS,
+    };
+
+    let mut names = Vec::new();
+
+    unsafe {
+        let Ok(snapshot) = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) else {
+            return names;
+        };
+
+        let mut entry = PROCESSENTRY32W {
+            dwSize: std::mem::size_of::<PROCESSENTRY32W>() as u32,
+            ..Default::default()
+        };
+        let mut more = Process32FirstW(snapshot, &mut entry).is_ok();
+        while more {
+            let length = entry
+                .szExeFile
+                .iter()
+                .position(|c| *c == 0)
+                .unwrap_or(entry.szExeFile.len());
+            names.push(String::from_utf16_lossy(&entry.szExeFile[..length]));
+            more = Process32NextW(snapshot, &mut entry).is_ok();
+        }
+
+        CloseHandle(snapshot).ok();
+    }
+
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn device(name: &str) -> CameraDevice {
+        CameraDevice::from_name_and_path(name, "")
+    }
+
+    #[test]
+    fn matches_remote_control_processes_by_stem() {
+        let processes = [
+            "TeamViewer.exe",
+            "explorer.exe",
+            "rustdesk",
+            "anydesk-helper",
+        ]
+        .map(String::from);
+
+        assert_eq!(
+            match_remote_control_processes(&processes),
+            ["TeamViewer.exe", "rustdesk"]
+        );
+    }
+
+    #[test]
+    fn report_carries_cameras_and_session() {
+        let devices = [
+            device("Integrated Camera"),
+            device("TeamViewer Camera"),
+            device("OBS Virtual Camera"),
+        ];
+        let session = SessionContext {
+            remote_desktop_session: false,
+            remote_control_processes: vec!["AnyDesk.exe".to_string()],
+        };
+
+        let report = remote_control_report_from(&devices, session.clone());
+
+        let names = report
+            .remote_control_cameras
+            .iter()
+            .map(|device| device.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["TeamViewer Camera"]);
+        assert_eq!(report.session, session);
+        assert!(report.remote_control_active());
+    }
+
+    #[test]
+    fn report_is_inactive_without_either_signal() {
+        let report =
+            remote_control_report_from(&[device("Integrated Camera")], SessionContext::default());
+
+        assert!(report.remote_control_cameras.is_empty());
+        assert!(!report.remote_control_active());
+    }
+
+    #[test]
+    fn remote_control_cameras_are_their_own_kind() {
+        assert_eq!(
+            virtual_camera_kind(&device("AnyDesk Virtual Webcam")),
+            Some(VirtualCameraKind::RemoteControl)
+        );
+        assert_eq!(
+            virtual_camera_kind(&device("OBS Virtual Camera")),
+            Some(VirtualCameraKind::Software)
+        );
+        assert_eq!(virtual_camera_kind(&device("Integrated Camera")), None);
+    }
+}
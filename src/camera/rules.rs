@@ -0,0 +1,139 @@
+use std::path::Path;
+use std::str::FromStr;
+
+use super::config::{ConfigParseError, DetectionConfig, Whitelist};
+
+/// Whether a rules document adds to the built-in rules or replaces them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RulesMode {
+    #[default]
+    Extend,
+    Replace,
+}
+
+/// Detection rules as read from a TOML or JSON document.
+///
+/// Every section is optional. VID/PID entries are pairs of four hex digits
+/// or `*`; CLSIDs are GUIDs, with or without braces.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct Rules {
+    pub mode: RulesMode,
+    pub name_keywords: Vec<String>,
+    pub clsids: Vec<String>,
+    pub vid_pids: Vec<(String, String)>,
+    pub whitelist: Whitelist,
+}
+
+impl Rules {
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ConfigParseError> {
+        std::fs::read_to_string(path)
+            .map_err(ConfigParseError::Io)?
+            .parse()
+    }
+
+    /// The built-in rules, in `Replace` mode so they load back unchanged.
+    pub fn defaults() -> Self {
+        let config = DetectionConfig::default();
+        Self {
+            mode: RulesMode::Replace,
+            name_keywords: config.name_keywords,
+            clsids: config.clsids,
+            vid_pids: config.vid_pids,
+            whitelist: config.whitelist,
+        }
+    }
+
+    pub fn to_toml(&self) -> String {
+        // Only strings, lists and a nested table; this always serializes.
+        toml::to_string(self).expect("rules serialize to TOML")
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("rules serialize to JSON")
+    }
+
+    /// The rules applied to the built-in defaults according to `mode`.
+    pub fn into_config(self) -> DetectionConfig {
+        let mut config = DetectionConfig::default();
+        match self.mode {
+            RulesMode::Extend => {
+                config.name_keywords.extend(self.name_keywords);
+                config.clsids.extend(self.clsids);
+                config.vid_pids.extend(self.vid_pids);
+                config.whitelist.names.extend(self.whitelist.names);
+                config.whitelist.vid_pids.extend(self.whitelist.vid_pids);
+            }
+            RulesMode::Replace => {
+                config.name_keywords = self.name_keywords;
+                config.clsids = self.clsids;
+                config.vid_pids = self.vid_pids;
+                config.whitelist = self.whitelist;
+            }
+        }
+        config
+    }
+
+    fn validate(&self) -> Result<(), ConfigParseError> {
+        let id_pairs = self.vid_pids.iter().map(|pair| ("vid_pids", pair)).chain(
+            self.whitelist
+                .vid_pids
+                .iter()
+                .map(|pair| ("whitelist.vid_pids", pair)),
+        );
+        for (field, (vid, pid)) in id_pairs {
+            for id in [vid, pid] {
+                if !is_valid_id(id) {
+                    return Err(invalid(field, id));
+                }
+            }
+        }
+
+        if let Some(clsid) = self.clsids.iter().find(|clsid| !is_valid_guid(clsid)) {
+            return Err(invalid("clsids", clsid));
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Rules {
+    type Err = ConfigParseError;
+
+    /// Parses a JSON document when it starts with `{`, TOML otherwise, and
+    /// validates it.
+    fn from_str(rules: &str) -> Result<Self, Self::Err> {
+        let parsed: Self = if rules.trim_start().starts_with('{') {
+            serde_json::from_str(rules).map_err(ConfigParseError::Json)?
+        } else {
+            toml::from_str(rules).map_err(ConfigParseError::Toml)?
+        };
+        parsed.validate()?;
+        Ok(parsed)
+    }
+}
+
+fn invalid(field: &str, value: &str) -> ConfigParseError {
+    ConfigParseError::Invalid {
+        field: field.to_string(),
+        value: value.to_string(),
+    }
+}
+
+fn is_valid_id(id: &str) -> bool {
+    id == "*" || (id.len() == 4 && id.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+// 8-4-4-4-12 hex digits, optionally in braces.
+fn is_valid_guid(guid: &str) -> bool {
+    let guid = guid
+        .strip_prefix('{')
+        .and_then(|guid| guid.strip_suffix('}'))
+        .unwrap_or(guid);
+    let groups = guid.split('-').collect::<Vec<_>>();
+    groups.len() == 5
+        && groups
+            .iter()
+            .zip([8, 4, 4, 4, 12])
+            .all(|(group, len)| group.len() == len && group.chars().all(|c| c.is_ascii_hexdigit()))
+}
@@ -0,0 +1,379 @@
+use super::config::DetectionConfig;
+use super::device_enum::{
+    driver_identifies_device, has_hardware_path, has_known_real_vid, has_usb_path, is_allowlisted,
+    keyword_matches, matching_vid_pid_rule, rule_reason, CameraDevice, DetectionReason,
+};
+use super::remote_control::is_remote_control_camera;
+
+/// Points each signal adds to a device's `VirtualScore`; negative points
+/// count towards a real camera.
+///
+/// The points only decide the classification once
+/// `DetectionConfig::score_threshold` is set. With the defaults and a
+/// threshold of 50, each rule on its own classifies a device the way
+/// `classify` does. Signals add up, though: a name keyword and a matching
+/// CLSID outweigh a known real vendor, which `classify` treats as final,
+/// and a manufacturer keyword on a device without a hardware path is
+/// enough. The strict modes are not scored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ScoreWeights {
+    /// A name keyword in the device name.
+    pub name_keyword: i32,
+    /// A name keyword in the manufacturer.
+    pub manufacturer_keyword: i32,
+    /// A name keyword matching a whole segment of the device path.
+    pub path_keyword: i32,
+    pub vid_pid: i32,
+    pub clsid: i32,
+    /// A Windows device that does not come from USB, PCI or ACPI.
+    pub no_hardware_path: i32,
+    /// A listed driver service, or a keyword in a V4L2 kernel driver or a
+    /// macOS model ID.
+    pub software_driver: i32,
+    pub remote_control: i32,
+    /// A known real VID on a USB path, unless a VID/PID rule names the
+    /// device.
+    pub known_real_vendor: i32,
+}
+
+impl Default for ScoreWeights {
+    fn default() -> Self {
+        Self {
+            name_keyword: 60,
+            manufacturer_keyword: 30,
+            path_keyword: 60,
+            vid_pid: 90,
+            clsid: 90,
+            no_hardware_path: 40,
+            software_driver: 50,
+            remote_control: 100,
+            known_real_vendor: -70,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ScoreSignal {
+    NameKeyword,
+    ManufacturerKeyword,
+    PathKeyword,
+    VidPid,
+    Clsid,
+    NoHardwarePath,
+    SoftwareDriver,
+    RemoteControl,
+    KnownRealVendor,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScoreContribution {
+    pub signal: ScoreSignal,
+    pub points: i32,
+}
+
+/// The signals that matched a device and the points each one added.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VirtualScore {
+    pub total: i32,
+    /// `DetectionConfig::score_threshold`; without one, `total` does not
+    /// affect the classification.
+    pub threshold: Option<i32>,
+    /// Whitelisted devices are real whatever their score.
+    pub whitelisted: bool,
+    pub contributions: Vec<ScoreContribution>,
+    /// What `classify` reports for the device, serial numbers aside.
+    pub reason: DetectionReason,
+}
+
+impl VirtualScore {
+    pub fn is_virtual(&self) -> bool {
+        self.reason.is_virtual()
+    }
+}
+
+/// Scores `device` under the built-in rules and weights.
+///
+/// The built-in configuration has no `score_threshold`, so the device is
+/// still classified by its strongest rule; the contributions show what
+/// else matched.
+pub fn classify_scored(device: &CameraDevice) -> VirtualScore {
+    score_with(device, &DetectionConfig::default())
+}
+
+pub(crate) fn score_with(device: &CameraDevice, config: &DetectionConfig) -> VirtualScore {
+    let weights = &config.score_weights;
+    let mut contributions = Vec::new();
+    let mut add = |signal, points| contributions.push(ScoreContribution { signal, points });
+
+    if has_keyword(&device.name, config, false) {
+        add(ScoreSignal::NameKeyword, weights.name_keyword);
+    }
+
+    if device
+        .manufacturer
+        .as_deref()
+        .is_some_and(|manufacturer| has_keyword(manufacturer, config, false))
+    {
+        add(
+            ScoreSignal::ManufacturerKeyword,
+            weights.manufacturer_keyword,
+        );
+    }
+
+    if device
+        .device_path
+        .as_deref()
+        .is_some_and(|path| has_keyword(path, config, true))
+    {
+        add(ScoreSignal::PathKeyword, weights.path_keyword);
+    }
+
+    let vid_pid_rule = matching_vid_pid_rule(device, config);
+    if vid_pid_rule.is_some() {
+        add(ScoreSignal::VidPid, weights.vid_pid);
+    }
+
+    let clsid_matches = device.clsid.as_deref().is_some_and(|clsid| {
+        let clsid = clsid.to_lowercase();
+        config
+            .clsids
+            .iter()
+            .any(|rule| clsid.contains(&rule.to_lowercase()))
+    });
+    if clsid_matches {
+        add(ScoreSignal::Clsid, weights.clsid);
+    }
+
+    if !has_hardware_path(device) {
+        add(ScoreSignal::NoHardwarePath, weights.no_hardware_path);
+    }
+
+    if is_software_driver(device, config) {
+        add(ScoreSignal::SoftwareDriver, weights.software_driver);
+    }
+
+    if is_remote_control_camera(device) {
+        add(ScoreSignal::RemoteControl, weights.remote_control);
+    }
+
+    if vid_pid_rule.is_none() && has_known_real_vid(device, config) && has_usb_path(device) {
+        add(ScoreSignal::KnownRealVendor, weights.known_real_vendor);
+    }
+
+    let total = contributions
+        .iter()
+        .map(|contribution| contribution.points)
+        .sum();
+    let whitelisted = is_allowlisted(device, config);
+    let reason = match config.score_threshold {
+        Some(threshold) if !whitelisted && total >= threshold => DetectionReason::Score(total),
+        Some(_) => DetectionReason::Real,
+        None => rule_reason(device, config),
+    };
+    VirtualScore {
+        total,
+        threshold: config.score_threshold,
+        whitelisted,
+        contributions,
+        reason,
+    }
+}
+
+fn has_keyword(field: &str, config: &DetectionConfig, whole_tokens: bool) -> bool {
+    let field = config.fold_case(field);
+    config
+        .name_keywords
+        .iter()
+        .any(|keyword| keyword_matches(&field, &config.fold_case(keyword), whole_tokens))
+}
+
+fn is_software_driver(device: &CameraDevice, config: &DetectionConfig) -> bool {
+    let Some(driver) = device.driver.as_deref() else {
+        return false;
+    };
+    if config
+        .driver_services
+        .iter()
+        .any(|service| service.eq_ignore_ascii_case(driver))
+    {
+        return true;
+    }
+
+    driver_identifies_device(device) && has_keyword(driver, config, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::camera::device_enum::{is_virtual_camera, EnumerationSource};
+
+    fn device(name: &str, path: &str, source: EnumerationSource) -> CameraDevice {
+        CameraDevice {
+            enumeration_source: source,
+            ..CameraDevice::from_name_and_path(name, path)
+        }
+    }
+
+    fn usb(name: &str, vid_pid: &str) -> CameraDevice {
+        CameraDevice::from_name_and_path(
+            name,
+            &format!(r"\\?\usb#{vid_pid}#SN0001#{{e5323777-f976-4f5b-9b55-b94699c46e44}}\global"),
+        )
+    }
+
+    const OBS_CLSID: &str = "{A3FCE0F5-3493-419F-958A-ABA1250EC20B}";
+
+    // Each case lists the signals that match and whether the total reaches
+    // a score threshold of 50, which differs from `classify` only where
+    // signals add up.
+    #[test]
+    fn scores_agree_with_classify() {
+        use ScoreSignal::*;
+
+        let filter = |name: &str| device(name, "", EnumerationSource::DirectShow);
+        let cases = [
+            (
+                usb("Logitech C920", "vid_046d&pid_082d"),
+                vec![KnownRealVendor],
+                false,
+            ),
+            (usb("Camera", "vid_2222&pid_0001"), vec![], false),
+            (
+                usb("HP Virtual Camera", "vid_04f2&pid_b6dd"),
+                vec![NameKeyword, KnownRealVendor],
+                false,
+            ),
+            (usb("Capture", "vid_0bda&pid_58f4"), vec![VidPid], true),
+            (filter("Camera"), vec![NoHardwarePath], false),
+            (
+                filter("OBS Virtual Camera"),
+                vec![NameKeyword, NoHardwarePath],
+                true,
+            ),
+            (
+                CameraDevice {
+                    clsid: Some(OBS_CLSID.to_string()),
+                    ..filter("Camera")
+                },
+                vec![Clsid, NoHardwarePath],
+                true,
+            ),
+            (
+                device(
+                    "Video Source",
+                    r"\\?\root#manycam#0000#{e5323777-f976-4f5b-9b55-b94699c46e44}",
+                    EnumerationSource::DirectShow,
+                ),
+                vec![PathKeyword, NoHardwarePath],
+                true,
+            ),
+            (
+                CameraDevice {
+                    driver: Some("obs-virtualcam".to_string()),
+                    ..usb("Camera", "vid_2222&pid_0001")
+                },
+                vec![SoftwareDriver],
+                true,
+            ),
+            (
+                CameraDevice {
+                    driver: Some("v4l2 loopback".to_string()),
+                    ..device("Dummy video device", "/dev/video9", EnumerationSource::V4l2)
+                },
+                vec![SoftwareDriver],
+                true,
+            ),
+            (
+                CameraDevice {
+                    manufacturer: Some("obs-project".to_string()),
+                    ..device("Camera Extension", "", EnumerationSource::AvFoundation)
+                },
+                vec![ManufacturerKeyword],
+                false,
+            ),
+            (
+                CameraDevice {
+                    manufacturer: Some("obs-project".to_string()),
+                    driver: Some("OBS Camera Extension Model".to_string()),
+                    ..device("Camera Extension", "", EnumerationSource::AvFoundation)
+                },
+                vec![ManufacturerKeyword, SoftwareDriver],
+                true,
+            ),
+            // A known real vendor is final for `classify`, but a name keyword
+            // and a CLSID outweigh its points.
+            (
+                CameraDevice {
+                    clsid: Some(OBS_CLSID.to_string()),
+                    ..usb("Logitech Virtual Cam", "vid_046d&pid_0825")
+                },
+                vec![NameKeyword, Clsid, KnownRealVendor],
+                true,
+            ),
+            (
+                CameraDevice {
+                    manufacturer: Some("OBS Project".to_string()),
+                    ..usb("Logitech Virtual Cam", "vid_046d&pid_0825")
+                },
+                vec![NameKeyword, ManufacturerKeyword, KnownRealVendor],
+                false,
+            ),
+            // Neither signal flags a device on its own.
+            (
+                CameraDevice {
+                    manufacturer: Some("ManyCam".to_string()),
+                    ..filter("Camera")
+                },
+                vec![ManufacturerKeyword, NoHardwarePath],
+                true,
+            ),
+        ];
+
+        let scored = DetectionConfig::default().with_score_threshold(50);
+        for (device, signals, virtual_by_score) in cases {
+            let score = classify_scored(&device);
+            assert_eq!(
+                score
+                    .contributions
+                    .iter()
+                    .map(|contribution| contribution.signal)
+                    .collect::<Vec<_>>(),
+                signals,
+                "{}",
+                device.name
+            );
+            assert_eq!(
+                score.is_virtual(),
+                is_virtual_camera(&device),
+                "{}",
+                device.name
+            );
+            assert_eq!(
+                score_with(&device, &scored).is_virtual(),
+                virtual_by_score,
+                "{}",
+                device.name
+            );
+        }
+    }
+
+    #[test]
+    fn whitelisted_devices_are_never_virtual() {
+        let config = DetectionConfig {
+            whitelist: crate::camera::config::Whitelist::default().add_vid_pid("0bda", "58f4"),
+            ..DetectionConfig::default().with_score_threshold(50)
+        };
+
+        let score = score_with(&usb("OBS Virtual Camera", "vid_0bda&pid_58f4"), &config);
+
+        assert!(score.total >= 50);
+        assert!(score.whitelisted);
+        assert!(!score.is_virtual());
+        assert_eq!(score.reason, DetectionReason::Real);
+    }
+}
@@ -0,0 +1,124 @@
+use super::config::{DetectionConfig, KNOWN_VIRTUAL_CAMERAS};
+use super::device_enum::{classify_all, CameraDevice, DeviceKind, EnumerationSource};
+
+const KNOWN_REAL_CAMERAS: [(&str, &str, &str, &str); 2] = [
+    ("Logitech HD Pro Webcam C920", "Logitech", "046d", "082d"),
+    ("Microsoft LifeCam HD-3000", "Microsoft", "045e", "0810"),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelfTestFailure {
+    pub device: CameraDevice,
+    pub expected: DeviceKind,
+    pub actual: DeviceKind,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SelfTestReport {
+    pub checked: usize,
+    pub failures: Vec<SelfTestFailure>,
+}
+
+impl SelfTestReport {
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+pub fn self_test() -> SelfTestReport {
+    self_test_with(&DetectionConfig::default())
+}
+
+/// Checks `config` against the built-in known cameras.
+///
+/// Each known virtual camera is checked twice, once by name and once by its
+/// VID/PID alone, so a rule that no longer matches its own entry shows up.
+pub fn self_test_with(config: &DetectionConfig) -> SelfTestReport {
+    let mut cases = Vec::new();
+    for (name, vid, pid) in KNOWN_VIRTUAL_CAMERAS {
+        cases.push((synthetic_device(name, None, None), DeviceKind::Virtual));
+        cases.push((
+            synthetic_device("USB Camera", None, Some((vid, pid))),
+            DeviceKind::Virtual,
+        ));
+    }
+    for (name, manufacturer, vid, pid) in KNOWN_REAL_CAMERAS {
+        cases.push((
+            synthetic_device(name, Some(manufacturer), Some((vid, pid))),
+            DeviceKind::Real,
+        ));
+    }
+
+    let mut report = SelfTestReport::default();
+    for (device, expected) in cases {
+        // Classify one at a time so the devices cannot affect each other.
+        let (device, actual) = classify_all(vec![device], config).remove(0);
+        report.checked += 1;
+        if actual != expected {
+            report.failures.push(SelfTestFailure {
+                device,
+                expected,
+                actual,
+            });
+        }
+    }
+
+    report
+}
+
+fn synthetic_device(
+    name: &str,
+    manufacturer: Option<&str>,
+    ids: Option<(&str, &str)>,
+) -> CameraDevice {
+    CameraDevice {
+        name: name.to_string(),
+        manufacturer: manufacturer.map(str::to_string),
+        device_path: None,
+        driver: None,
+        vid: ids.map(|(vid, _)| vid.to_uppercase()),
+        pid: ids.map(|(_, pid)| pid.to_uppercase()),
+        clsid: None,
+        serial_number: None,
+        orientation: None,
+        mirrored: None,
+        built_in: None,
+        hardware_ids: Vec::new(),
+        instance_id: None,
+        enumeration_source: EnumerationSource::DirectShow,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_config_passes() {
+        let report = self_test();
+
+        assert_eq!(report.failures, []);
+        assert_eq!(
+            report.checked,
+            KNOWN_VIRTUAL_CAMERAS.len() * 2 + KNOWN_REAL_CAMERAS.len()
+        );
+    }
+
+    #[test]
+    fn reports_rules_that_miss_their_own_entries() {
+        let (name, _, _) = KNOWN_VIRTUAL_CAMERAS[0];
+        let config = DetectionConfig {
+            name_keywords: Vec::new(),
+            ..DetectionConfig::default()
+        };
+
+        let report = self_test_with(&config);
+
+        assert!(!report.passed());
+        assert!(report.failures.iter().any(|failure| {
+            failure.device.name == name
+                && failure.expected == DeviceKind::Virtual
+                && failure.actual == DeviceKind::Real
+        }));
+    }
+}
@@ -0,0 +1,65 @@
+use std::time::SystemTime;
+
+use super::device_enum::{enumerate_devices, CameraDevice};
+use super::error::EnumerationError;
+
+/// The devices present at one point in time.
+///
+/// Taking snapshots periodically and comparing them with `diff` finds
+/// cameras plugged in after an initial check without a `CameraWatcher`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CameraSnapshot {
+    pub devices: Vec<CameraDevice>,
+    pub taken_at: SystemTime,
+}
+
+impl CameraSnapshot {
+    pub fn take() -> Result<Self, EnumerationError> {
+        Ok(Self::new(enumerate_devices()?))
+    }
+
+    /// A snapshot of devices enumerated some other way, taken now.
+    pub fn new(devices: Vec<CameraDevice>) -> Self {
+        Self {
+            devices,
+            taken_at: SystemTime::now(),
+        }
+    }
+}
+
+/// The devices that appeared and disappeared between two snapshots.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CameraSnapshotDiff {
+    pub added: Vec<CameraDevice>,
+    pub removed: Vec<CameraDevice>,
+}
+
+impl CameraSnapshotDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Compares devices with `CameraDevice`'s equality, so a camera seen by
+/// another backend or with a new firmware revision is not reported as both
+/// removed and added.
+pub fn diff(before: &CameraSnapshot, after: &CameraSnapshot) -> CameraSnapshotDiff {
+    diff_devices(&before.devices, &after.devices)
+}
+
+pub(crate) fn diff_devices(before: &[CameraDevice], after: &[CameraDevice]) -> CameraSnapshotDiff {
+    CameraSnapshotDiff {
+        added: after
+            .iter()
+            .filter(|device| !before.contains(device))
+            .cloned()
+            .collect(),
+        removed: before
+            .iter()
+            .filter(|device| !after.contains(device))
+            .cloned()
+            .collect(),
+    }
+}
@@ -0,0 +1,356 @@
+use std::fmt;
+use std::sync::mpsc::Receiver;
+use std::thread::JoinHandle;
+
+use super::device_enum::CameraDevice;
+
+#[cfg(windows)]
+use std::sync::mpsc::{self, Sender};
+
+#[cfg(windows)]
+use super::device_enum::enumerate_devices;
+#[cfg(windows)]
+use super::error::EnumerationError;
+
+// KSCATEGORY_VIDEO_CAMERA, the interface class camera drivers and frame
+// server virtual cameras register.
+#[cfg(windows)]
+const VIDEO_CAMERA_INTERFACE: windows::core::GUID =
+    windows::core::GUID::from_u128(0xe5323777_f976_4f5b_9b55_b94699c46e44);
+
+#[cfg(windows)]
+const WM_DEVICES_CHANGED: u32 = windows::Win32::UI::WindowsAndMessaging::WM_APP + 1;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeviceEvent {
+    Added(CameraDevice),
+    Removed(CameraDevice),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum WatchError {
+    PlatformNotSupported,
+    WindowCreationFailed { hresult: i32 },
+    NotificationRegistrationFailed { hresult: i32 },
+}
+
+impl fmt::Display for WatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WatchError::PlatformNotSupported => {
+                write!(f, "device watching is not supported on this platform")
+            }
+            WatchError::WindowCreationFailed { hresult } => write!(
+                f,
+                "could not create the notification window (HRESULT {hresult:#010x})"
+            ),
+            WatchError::NotificationRegistrationFailed { hresult } => write!(
+                f,
+                "could not register for device notifications (HRESULT {hresult:#010x})"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for WatchError {}
+
+/// Keeps a `watch_devices` or `watch` watch running; dropping it stops it.
+#[derive(Debug)]
+pub struct DeviceWatcher {
+    #[cfg(windows)]
+    thread_id: u32,
+    #[cfg(windows)]
+    thread: Option<JoinHandle<()>>,
+}
+
+#[cfg(windows)]
+impl Drop for DeviceWatcher {
+    fn drop(&mut self) {
+        use windows::Win32::Foundation::{LPARAM, WPARAM};
+        use windows::Win32::UI::WindowsAndMessaging::{PostThreadMessageW, WM_QUIT};
+
+        unsafe {
+            // Fails when the thread already stopped because the receiver was dropped.
+            PostThreadMessageW(self.thread_id, WM_QUIT, WPARAM(0), LPARAM(0)).ok();
+        }
+        if let Some(thread) = self.thread.take() {
+            thread.join().ok();
+        }
+    }
+}
+
+/// Calls a callback for every `DeviceEvent`; dropping it stops the watch.
+#[derive(Debug)]
+pub struct CameraWatcher {
+    watcher: Option<DeviceWatcher>,
+    dispatcher: Option<JoinHandle<()>>,
+}
+
+impl CameraWatcher {
+    /// Starts `watch_devices` and runs `callback` on its own thread.
+    pub fn watch(
+        mut callback: impl FnMut(DeviceEvent) + Send + 'static,
+    ) -> Result<Self, WatchError> {
+        let (watcher, events) = watch_devices()?;
+        let dispatcher = std::thread::spawn(move || {
+            for event in events {
+                callback(event);
+            }
+        });
+
+        Ok(Self {
+            watcher: Some(watcher),
+            dispatcher: Some(dispatcher),
+        })
+    }
+}
+
+impl Drop for CameraWatcher {
+    fn drop(&mut self) {
+        // Stopping the watch closes the channel, which ends the dispatcher.
+        self.watcher = None;
+        if let Some(dispatcher) = self.dispatcher.take() {
+            dispatcher.join().ok();
+        }
+    }
+}
+
+/// Sends an event whenever a camera is plugged in or removed.
+///
+/// On Windows a background thread owns a hidden window registered for
+/// video camera interface notifications, and re-enumerates and diffs the
+/// devices on each one. Other platforms return
+/// `WatchError::PlatformNotSupported`.
+pub fn watch_devices() -> Result<(DeviceWatcher, Receiver<DeviceEvent>), WatchError> {
+    #[cfg(windows)]
+    {
+        let (sender, events) = mpsc::channel();
+        let watcher =
+            start_windows_watch(move |known, current| send_changes(&sender, known, current))?;
+        Ok((watcher, events))
+    }
+
+    #[cfg(not(windows))]
+    {
+        Err(WatchError::PlatformNotSupported)
+    }
+}
+
+/// Calls `callback` with the full device list each time a camera is plugged
+/// in or removed, until the returned watcher is dropped.
+///
+/// The callback runs on the watcher's thread; see `watch_devices`.
+pub fn watch(
+    mut callback: impl FnMut(Vec<CameraDevice>) + Send + 'static,
+) -> Result<DeviceWatcher, WatchError> {
+    #[cfg(windows)]
+    {
+        start_windows_watch(move |known, current| {
+            let changed = known.len() != current.len()
+                || current.iter().any(|device| !known.contains(device));
+            if changed {
+                callback(current.to_vec());
+            }
+            true
+        })
+    }
+
+    #[cfg(not(windows))]
+    {
+        let _ = &mut callback;
+        Err(WatchError::PlatformNotSupported)
+    }
+}
+
+// `on_change` gets the previous and the new device list after every
+// notification and returns false to stop the watch.
+#[cfg(windows)]
+fn start_windows_watch(
+    on_change: impl FnMut(&[CameraDevice], &[CameraDevice]) -> bool + Send + 'static,
+) -> Result<DeviceWatcher, WatchError> {
+    use windows::Win32::Foundation::E_UNEXPECTED;
+
+    let (ready_sender, ready) = mpsc::channel();
+    let thread = std::thread::spawn(move || run_message_loop(on_change, ready_sender));
+
+    let started = ready
+        .recv()
+        .unwrap_or(Err(WatchError::WindowCreationFailed {
+            hresult: E_UNEXPECTED.0,
+        }));
+    match started {
+        Ok(thread_id) => Ok(DeviceWatcher {
+            thread_id,
+            thread: Some(thread),
+        }),
+        Err(error) => {
+            thread.join().ok();
+            Err(error)
+        }
+    }
+}
+
+#[cfg(windows)]
+fn run_message_loop(
+    mut on_change: impl FnMut(&[CameraDevice], &[CameraDevice]) -> bool,
+    ready: Sender<Result<u32, WatchError>>,
+) {
+    use windows::Win32::System::Threading::GetCurrentThreadId;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        DestroyWindow, DispatchMessageW, GetMessageW, PeekMessageW, RegisterDeviceNotificationW,
+        UnregisterDeviceNotification, DBT_DEVTYP_DEVICEINTERFACE, DEVICE_NOTIFY_WINDOW_HANDLE,
+        DEV_BROADCAST_DEVICEINTERFACE_W, MSG, PM_REMOVE,
+    };
+
+    unsafe {
+        let window = match create_notification_window() {
+            Ok(window) => window,
+            Err(error) => {
+                ready.send(Err(error)).ok();
+                return;
+            }
+        };
+
+        let filter = DEV_BROADCAST_DEVICEINTERFACE_W {
+            dbcc_size: std::mem::size_of::<DEV_BROADCAST_DEVICEINTERFACE_W>() as u32,
+            dbcc_devicetype: DBT_DEVTYP_DEVICEINTERFACE.0,
+            dbcc_classguid: VIDEO_CAMERA_INTERFACE,
+            ..Default::default()
+        };
+        let notification = match RegisterDeviceNotificationW(
+            window,
+            &filter as *const _ as *const std::ffi::c_void,
+            DEVICE_NOTIFY_WINDOW_HANDLE,
+        ) {
+            Ok(notification) => notification,
+            Err(error) => {
+                DestroyWindow(window).ok();
+                ready
+                    .send(Err(WatchError::NotificationRegistrationFailed {
+                        hresult: error.code().0,
+                    }))
+                    .ok();
+                return;
+            }
+        };
+
+        // Registered before the first snapshot, so no change falls in between.
+        let mut known = enumerate_devices().unwrap_or_else(EnumerationError::into_partial_devices);
+        ready.send(Ok(GetCurrentThreadId())).ok();
+
+        let mut message = MSG::default();
+        // 0 means WM_QUIT and -1 an error; both end the watch.
+        while GetMessageW(&mut message, None, 0, 0).0 > 0 {
+            if message.message != WM_DEVICES_CHANGED {
+                DispatchMessageW(&message);
+                continue;
+            }
+
+            // One re-enumeration covers every notification queued so far.
+            while PeekMessageW(
+                &mut message,
+                window,
+                WM_DEVICES_CHANGED,
+                WM_DEVICES_CHANGED,
+                PM_REMOVE,
+            )
+            .as_bool()
+            {}
+            let Ok(current) = enumerate_devices() else {
+                continue;
+            };
+            if !on_change(&known, &current) {
+                break;
+            }
+            known = current;
+        }
+
+        UnregisterDeviceNotification(notification).ok();
+        DestroyWindow(window).ok();
+    }
+}
+
+#[cfg(windows)]
+unsafe fn create_notification_window() -> Result<windows::Win32::Foundation::HWND, WatchError> {
+    use windows::core::{w, PCWSTR};
+    use windows::Win32::Foundation::HINSTANCE;
+    use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        CreateWindowExW, RegisterClassW, HWND_MESSAGE, WINDOW_EX_STYLE, WINDOW_STYLE, WNDCLASSW,
+    };
+
+    let class_name = w!("CameraSimpleDetectWatcher");
+    let instance: HINSTANCE = GetModuleHandleW(None)
+        .map_err(|error| WatchError::WindowCreationFailed {
+            hresult: error.code().0,
+        })?
+        .into();
+
+    let class = WNDCLASSW {
+        lpfnWndProc: Some(notification_window_proc),
+        hInstance: instance,
+        lpszClassName: class_name,
+        ..Default::default()
+    };
+    // Fails once the class exists, which is the case for every watcher after the first.
+    RegisterClassW(&class);
+
+    CreateWindowExW(
+        WINDOW_EX_STYLE::default(),
+        class_name,
+        PCWSTR::null(),
+        WINDOW_STYLE::default(),
+        0,
+        0,
+        0,
+        0,
+        HWND_MESSAGE,
+        None,
+        instance,
+        None,
+    )
+    .map_err(|error| WatchError::WindowCreationFailed {
+        hresult: error.code().0,
+    })
+}
+
+#[cfg(windows)]
+unsafe extern "system" fn notification_window_proc(
+    window: windows::Win32::Foundation::HWND,
+    message: u32,
+    wparam: windows::Win32::Foundation::WPARAM,
+    lparam: windows::Win32::Foundation::LPARAM,
+) -> windows::Win32::Foundation::LRESULT {
+    use windows::Win32::Foundation::{LPARAM, LRESULT, WPARAM};
+    use windows::Win32::UI::WindowsAndMessaging::{
+        DefWindowProcW, PostMessageW, DBT_DEVICEARRIVAL, DBT_DEVICEREMOVECOMPLETE, WM_DEVICECHANGE,
+    };
+
+    if message == WM_DEVICECHANGE
+        && matches!(
+            wparam.0 as u32,
+            DBT_DEVICEARRIVAL | DBT_DEVICEREMOVECOMPLETE
+        )
+    {
+        // WM_DEVICECHANGE is sent rather than posted, so GetMessageW never
+        // returns it; queue a message the loop does see.
+        PostMessageW(window, WM_DEVICES_CHANGED, WPARAM(0), LPARAM(0)).ok();
+        return LRESULT(1);
+    }
+
+    DefWindowProcW(window, message, wparam, lparam)
+}
+
+// Returns false once the receiver has been dropped.
+#[cfg(windows)]
+fn send_changes(
+    events: &Sender<DeviceEvent>,
+    known: &[CameraDevice],
+    current: &[CameraDevice],
+) -> bool {
+    let changes = super::snapshot::diff_devices(known, current);
+    let removed = changes.removed.into_iter().map(DeviceEvent::Removed);
+    let added = changes.added.into_iter().map(DeviceEvent::Added);
+    removed.chain(added).all(|event| events.send(event).is_ok())
+}
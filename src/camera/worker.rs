@@ -0,0 +1,82 @@
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Sender};
+use std::sync::OnceLock;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+static JOBS: OnceLock<Sender<Job>> = OnceLock::new();
+
+/// Runs `work` on the shared enumeration thread and waits for it without
+/// blocking the executor.
+///
+/// The thread is started on first use and lives as long as the process. On
+/// Windows it stays in the multithreaded apartment with Media Foundation
+/// started, so later calls skip that setup. Dropping the future abandons
+/// the result; the job still finishes and the thread moves on to the next
+/// one. A panic in `work` is resumed in the caller.
+pub(crate) async fn run_on_worker<T: Send + 'static>(work: fn() -> T) -> T {
+    let (sender, receiver) = tokio::sync::oneshot::channel::<Result<T, Box<dyn Any + Send>>>();
+    let job: Job = Box::new(move || {
+        sender
+            .send(panic::catch_unwind(AssertUnwindSafe(work)))
+            .ok();
+    });
+    JOBS.get_or_init(start_worker)
+        .send(job)
+        .expect("the enumeration thread never stops");
+
+    match receiver
+        .await
+        .expect("the enumeration thread answers every job")
+    {
+        Ok(value) => value,
+        Err(payload) => panic::resume_unwind(payload),
+    }
+}
+
+fn start_worker() -> Sender<Job> {
+    let (jobs, incoming) = mpsc::channel::<Job>();
+    std::thread::Builder::new()
+        .name("camera-enumeration".to_string())
+        .spawn(move || {
+            #[cfg(windows)]
+            let _session = WorkerSession::start();
+            for job in incoming {
+                job();
+            }
+        })
+        .expect("failed to spawn the enumeration thread");
+    jobs
+}
+
+// Keeps COM and Media Foundation initialized on the worker; the backends'
+// own MFStartup calls then only bump a reference count.
+#[cfg(windows)]
+struct WorkerSession {
+    _com: Option<super::platform::windows::ComApartment>,
+    media_foundation: bool,
+}
+
+#[cfg(windows)]
+impl WorkerSession {
+    fn start() -> Self {
+        use windows::Win32::Media::MediaFoundation::{MFStartup, MF_VERSION};
+
+        let com = super::platform::windows::ComApartment::enter().ok();
+        let media_foundation = unsafe { MFStartup(MF_VERSION, 0) }.is_ok();
+        Self {
+            _com: com,
+            media_foundation,
+        }
+    }
+}
+
+#[cfg(windows)]
+impl Drop for WorkerSession {
+    fn drop(&mut self) {
+        if self.media_foundation {
+            unsafe { windows::Win32::Media::MediaFoundation::MFShutdown() }.ok();
+        }
+    }
+}
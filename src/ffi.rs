@@ -0,0 +1,329 @@
+//! C API, enabled by the `capi` feature.
+//!
+//! The build script writes the matching header to
+//! `$OUT_DIR/camera_simple_detect.h`.
+
+use std::ffi::{c_char, CString};
+use std::ptr;
+
+use crate::camera::{
+    detect_cameras_checked, enumerate_devices, CameraDevice, DetectionResult, EnumerationError,
+    EnumerationSource,
+};
+
+pub const CAMERA_DETECT_REAL: u8 = 0;
+pub const CAMERA_DETECT_VIRTUAL: u8 = 1;
+pub const CAMERA_DETECT_NONE: u8 = 2;
+pub const CAMERA_DETECT_MIXED: u8 = 3;
+pub const CAMERA_DETECT_NETWORK: u8 = 4;
+pub const CAMERA_DETECT_ERROR: u8 = 255;
+
+pub const CSD_OK: i32 = 0;
+/// Some backends failed; the devices of the others were returned.
+pub const CSD_PARTIAL: i32 = 1;
+pub const CSD_INVALID_ARGUMENT: i32 = -1;
+pub const CSD_ENUMERATION_FAILED: i32 = -2;
+
+/// A device as seen from C.
+///
+/// Strings are NUL-terminated UTF-8 and null when the value is unknown.
+/// `orientation` is -1 and `mirrored` is -1 when unknown.
+/// `enumeration_source` is 0 for Media Foundation, 1 for DirectShow, 2 for
+/// V4L2, 3 for AVFoundation, 4 for the Windows registry and 5 for SetupAPI.
+#[repr(C)]
+pub struct CCameraDevice {
+    pub name: *const c_char,
+    pub manufacturer: *const c_char,
+    pub device_path: *const c_char,
+    pub driver: *const c_char,
+    pub vid: *const c_char,
+    pub pid: *const c_char,
+    pub clsid: *const c_char,
+    pub serial_number: *const c_char,
+    pub orientation: i32,
+    pub mirrored: i8,
+    pub enumeration_source: u8,
+}
+
+impl CCameraDevice {
+    fn from_device(device: CameraDevice) -> Self {
+        Self {
+            name: into_c_string(Some(device.name)),
+            manufacturer: into_c_string(device.manufacturer),
+            device_path: into_c_string(device.device_path),
+            driver: into_c_string(device.driver),
+            vid: into_c_string(device.vid),
+            pid: into_c_string(device.pid),
+            clsid: into_c_string(device.clsid),
+            serial_number: into_c_string(device.serial_number),
+            orientation: device.orientation.unwrap_or(-1),
+            mirrored: device.mirrored.map_or(-1, i8::from),
+            enumeration_source: match device.enumeration_source {
+                EnumerationSource::MediaFoundation => 0,
+                EnumerationSource::DirectShow => 1,
+                EnumerationSource::V4l2 => 2,
+                EnumerationSource::AvFoundation => 3,
+                EnumerationSource::Registry => 4,
+                EnumerationSource::SetupApi => 5,
+            },
+        }
+    }
+
+    unsafe fn free_strings(&mut self) {
+        for field in [
+            self.name,
+            self.manufacturer,
+            self.device_path,
+            self.driver,
+            self.vid,
+            self.pid,
+            self.clsid,
+            self.serial_number,
+        ] {
+            if !field.is_null() {
+                drop(CString::from_raw(field as *mut c_char));
+            }
+        }
+    }
+}
+
+/// The device type of the `csd_` functions.
+pub type CsdDevice = CCameraDevice;
+
+fn into_c_string(value: Option<String>) -> *const c_char {
+    // Interior NULs cannot be represented; such values are dropped.
+    value
+        .and_then(|value| CString::new(value).ok())
+        .map_or(ptr::null(), |value| value.into_raw() as *const c_char)
+}
+
+/// Enumerates cameras and returns a heap-allocated array of `*out_len` devices.
+///
+/// When only some backends fail, the devices that were found are returned.
+/// Returns null with `*out_len == 0` when nothing could be enumerated.
+///
+/// # Safety
+///
+/// `out_len` must be a valid pointer to a `usize`. The returned array must be
+/// released with `camera_free_devices`.
+#[no_mangle]
+pub unsafe extern "C" fn camera_enumerate_devices(out_len: *mut usize) -> *mut CCameraDevice {
+    let devices = enumerate_devices().unwrap_or_else(EnumerationError::into_partial_devices);
+    let (devices, len) = into_c_devices(devices);
+    if !out_len.is_null() {
+        *out_len = len;
+    }
+    devices
+}
+
+fn into_c_devices(devices: Vec<CameraDevice>) -> (*mut CCameraDevice, usize) {
+    let devices = devices
+        .into_iter()
+        .map(CCameraDevice::from_device)
+        .collect::<Box<[_]>>();
+    let len = devices.len();
+    if len == 0 {
+        return (ptr::null_mut(), 0);
+    }
+    (Box::into_raw(devices) as *mut CCameraDevice, len)
+}
+
+/// Frees an array returned by `camera_enumerate_devices`.
+///
+/// # Safety
+///
+/// `devices` and `len` must come from a single `camera_enumerate_devices`
+/// call, and the array must not be used afterwards. Null is ignored.
+#[no_mangle]
+pub unsafe extern "C" fn camera_free_devices(devices: *mut CCameraDevice, len: usize) {
+    if devices.is_null() {
+        return;
+    }
+    let mut devices = Box::from_raw(ptr::slice_from_raw_parts_mut(devices, len));
+    for device in devices.iter_mut() {
+        device.free_strings();
+    }
+}
+
+/// Returns `CAMERA_DETECT_REAL` (0), `CAMERA_DETECT_VIRTUAL` (1),
+/// `CAMERA_DETECT_NONE` (2), `CAMERA_DETECT_MIXED` (3) or
+/// `CAMERA_DETECT_NETWORK` (4), or `CAMERA_DETECT_ERROR` (255) when
+/// enumeration failed.
+#[no_mangle]
+pub extern "C" fn camera_detect() -> u8 {
+    match detect_cameras_checked() {
+        Ok(DetectionResult::RealCamera) => CAMERA_DETECT_REAL,
+        Ok(DetectionResult::VirtualCamera) => CAMERA_DETECT_VIRTUAL,
+        Ok(DetectionResult::NoCamera) => CAMERA_DETECT_NONE,
+        Ok(DetectionResult::Mixed { .. }) => CAMERA_DETECT_MIXED,
+        Ok(DetectionResult::NetworkCamera) => CAMERA_DETECT_NETWORK,
+        Err(_) => CAMERA_DETECT_ERROR,
+    }
+}
+
+/// Enumerates cameras into `*out_list`, an array of `*out_count` devices
+/// that owns all of its strings.
+///
+/// Returns `CSD_OK`, `CSD_PARTIAL` when only some backends failed,
+/// `CSD_ENUMERATION_FAILED` with an empty list, or `CSD_INVALID_ARGUMENT`
+/// when a pointer is null. An empty list is null.
+///
+/// # Safety
+///
+/// `out_list` and `out_count` must be valid for writes. The list must be
+/// released with `csd_free_list`.
+#[no_mangle]
+pub unsafe extern "C" fn csd_enumerate(
+    out_list: *mut *mut CsdDevice,
+    out_count: *mut usize,
+) -> i32 {
+    if out_list.is_null() || out_count.is_null() {
+        return CSD_INVALID_ARGUMENT;
+    }
+
+    let (devices, status) = match enumerate_devices() {
+        Ok(devices) => (devices, CSD_OK),
+        Err(EnumerationError::Partial(devices, _)) => (devices, CSD_PARTIAL),
+        Err(_) => (Vec::new(), CSD_ENUMERATION_FAILED),
+    };
+    (*out_list, *out_count) = into_c_devices(devices);
+    status
+}
+
+/// Frees a list returned by `csd_enumerate`, strings included.
+///
+/// # Safety
+///
+/// `list` and `count` must come from a single `csd_enumerate` call, and the
+/// list must not be used afterwards. Null is ignored.
+#[no_mangle]
+pub unsafe extern "C" fn csd_free_list(list: *mut CsdDevice, count: usize) {
+    camera_free_devices(list, count);
+}
+
+/// The same values as `camera_detect`, with -1 instead of 255 on error.
+#[no_mangle]
+pub extern "C" fn csd_detect() -> i32 {
+    match camera_detect() {
+        CAMERA_DETECT_ERROR => -1,
+        result => i32::from(result),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CStr;
+    use std::mem::{align_of, offset_of, size_of};
+
+    use super::*;
+
+    unsafe fn c_str(value: *const c_char) -> Option<&'static str> {
+        (!value.is_null()).then(|| CStr::from_ptr(value).to_str().unwrap())
+    }
+
+    #[test]
+    fn c_camera_device_layout() {
+        let pointer = size_of::<*const c_char>();
+
+        assert_eq!(offset_of!(CCameraDevice, name), 0);
+        assert_eq!(offset_of!(CCameraDevice, serial_number), 7 * pointer);
+        assert_eq!(offset_of!(CCameraDevice, orientation), 8 * pointer);
+        assert_eq!(offset_of!(CCameraDevice, mirrored), 8 * pointer + 4);
+        assert_eq!(
+            offset_of!(CCameraDevice, enumeration_source),
+            8 * pointer + 5
+        );
+        assert_eq!(align_of::<CCameraDevice>(), align_of::<*const c_char>());
+        assert_eq!(
+            size_of::<CCameraDevice>(),
+            (8 * pointer + 6).next_multiple_of(pointer)
+        );
+    }
+
+    #[test]
+    fn devices_cross_the_c_abi() {
+        let free: unsafe extern "C" fn(*mut CCameraDevice, usize) = camera_free_devices;
+        let devices = vec![
+            CameraDevice {
+                orientation: Some(180),
+                mirrored: Some(true),
+                ..CameraDevice::from_name_and_path(
+                    "USB Camera",
+                    r"\\?\usb#vid_046d&pid_082d#A1B2C3#{e5323777-f976-4f5b-9b55-b94699c46e44}\global",
+                )
+            },
+            CameraDevice {
+                enumeration_source: EnumerationSource::DirectShow,
+                ..CameraDevice::from_name_and_path("Bad\0Name", "")
+            },
+        ];
+
+        let (list, len) = into_c_devices(devices);
+        assert_eq!(len, 2);
+        unsafe {
+            let first = &*list;
+            assert_eq!(c_str(first.name), Some("USB Camera"));
+            assert_eq!(c_str(first.vid), Some("046D"));
+            assert_eq!(c_str(first.serial_number), Some("A1B2C3"));
+            assert_eq!(c_str(first.manufacturer), None);
+            assert_eq!(first.orientation, 180);
+            assert_eq!(first.mirrored, 1);
+            assert_eq!(first.enumeration_source, 0);
+
+            let second = &*list.add(1);
+            assert_eq!(c_str(second.name), None);
+            assert_eq!(c_str(second.device_path), None);
+            assert_eq!(second.orientation, -1);
+            assert_eq!(second.mirrored, -1);
+            assert_eq!(second.enumeration_source, 1);
+
+            free(list, len);
+            free(ptr::null_mut(), 0);
+        }
+    }
+
+    #[test]
+    fn enumeration_through_the_c_abi() {
+        let enumerate: unsafe extern "C" fn(*mut usize) -> *mut CCameraDevice =
+            camera_enumerate_devices;
+
+        unsafe {
+            let mut len = usize::MAX;
+            let devices = enumerate(&mut len);
+            assert_eq!(devices.is_null(), len == 0);
+            for index in 0..len {
+                assert!(c_str((*devices.add(index)).name).is_some());
+            }
+            camera_free_devices(devices, len);
+        }
+    }
+
+    #[test]
+    fn csd_enumeration_through_the_c_abi() {
+        let enumerate: unsafe extern "C" fn(*mut *mut CsdDevice, *mut usize) -> i32 = csd_enumerate;
+        let free: unsafe extern "C" fn(*mut CsdDevice, usize) = csd_free_list;
+
+        unsafe {
+            let mut list = ptr::null_mut();
+            let mut count = 0;
+            assert_eq!(enumerate(ptr::null_mut(), &mut count), CSD_INVALID_ARGUMENT);
+            assert_eq!(enumerate(&mut list, ptr::null_mut()), CSD_INVALID_ARGUMENT);
+
+            let status = enumerate(&mut list, &mut count);
+            assert!([CSD_OK, CSD_PARTIAL, CSD_ENUMERATION_FAILED].contains(&status));
+            assert_eq!(list.is_null(), count == 0);
+            free(list, count);
+        }
+    }
+
+    #[test]
+    fn detect_codes_agree() {
+        let detect: extern "C" fn() -> u8 = camera_detect;
+        let csd_detect: extern "C" fn() -> i32 = csd_detect;
+
+        match detect() {
+            CAMERA_DETECT_ERROR => assert_eq!(csd_detect(), -1),
+            result => assert_eq!(csd_detect(), i32::from(result)),
+        }
+    }
+}
@@ -1 +1,106 @@
 pub mod camera;
+#[cfg(feature = "capi")]
+pub mod ffi;
+pub mod prelude;
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    const SNAPSHOT: &str = "api/public-api.txt";
+
+    // The exported names, each with the cfg that gates it, compared against
+    // the committed snapshot so that changes to the public surface are
+    // deliberate. Set UPDATE_PUBLIC_API=1 to rewrite the snapshot.
+    #[test]
+    fn public_api_matches_snapshot() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let read = |path: &str| std::fs::read_to_string(root.join(path)).unwrap();
+
+        let mut api = [
+            ("", "src/lib.rs"),
+            ("prelude", "src/prelude.rs"),
+            ("camera", "src/camera/mod.rs"),
+            ("ffi", "src/ffi.rs"),
+        ]
+        .into_iter()
+        .flat_map(|(module, path)| exports(module, &read(path)))
+        .collect::<Vec<_>>();
+        api.sort();
+        let api = api.join("\n") + "\n";
+
+        if std::env::var_os("UPDATE_PUBLIC_API").is_some() {
+            std::fs::write(root.join(SNAPSHOT), &api).unwrap();
+        }
+        assert!(
+            api == read(SNAPSHOT),
+            "the public API changed; rerun with UPDATE_PUBLIC_API=1 and commit {SNAPSHOT}\n{api}"
+        );
+    }
+
+    // Top-level `pub` items and `pub use` lists of a module's source.
+    fn exports(module: &str, source: &str) -> Vec<String> {
+        let mut exports = Vec::new();
+        let mut cfg = String::new();
+        let mut statement = String::new();
+
+        for line in source.lines() {
+            if statement.is_empty() {
+                if let Some(condition) = line.strip_prefix("#[cfg(") {
+                    cfg = format!(" #[cfg({condition}");
+                    continue;
+                }
+                if line.starts_with("#[") {
+                    continue;
+                }
+                if !line.starts_with("pub ") {
+                    cfg.clear();
+                    continue;
+                }
+            }
+
+            statement.push_str(line.trim());
+            statement.push(' ');
+            if statement.starts_with("pub use ") && !line.ends_with(';') {
+                continue;
+            }
+
+            let path = |name: &str| match module {
+                "" => name.to_string(),
+                module => format!("{module}::{name}"),
+            };
+            if let Some(list) = statement.strip_prefix("pub use ") {
+                let list = list.trim().trim_end_matches(';');
+                let names = match list.split_once('{') {
+                    Some((_, names)) => names.trim_end_matches('}').split(',').collect(),
+                    None => vec![list],
+                };
+                for name in names
+                    .into_iter()
+                    .map(str::trim)
+                    .filter(|name| !name.is_empty())
+                {
+                    let name = name.rsplit("::").next().unwrap();
+                    exports.push(format!("use {}{cfg}", path(name)));
+                }
+            } else {
+                let mut words = statement
+                    .split_whitespace()
+                    .skip(1)
+                    .skip_while(|word| ["unsafe", "extern", "\"C\""].contains(word));
+                let kind = words.next().unwrap();
+                let name = words
+                    .next()
+                    .unwrap()
+                    .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+                    .next()
+                    .unwrap();
+                exports.push(format!("{kind} {}{cfg}", path(name)));
+            }
+            statement.clear();
+            cfg.clear();
+        }
+
+        exports
+    }
+}
@@ -0,0 +1,6 @@
+pub use crate::camera::{
+    classify_devices, detect_cameras_checked, detect_cameras_with, enumerate_devices,
+    enumerate_real_cameras, enumerate_virtual_cameras, CameraDevice, DetectionConfig,
+    DetectionResult, DeviceKind, EnumerationError, EnumerationSource, OpenFailureDiagnosis,
+    VirtualCameraKind,
+};